@@ -1,96 +1,596 @@
 use std::env;
-use std::path::PathBuf;
-use std::process::{Command, Stdio};
+use std::fmt;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::process::Command;
 
-const CMD_CLONE_SPIRV_HEADERS: [&'static str; 4] = [
-    "git", "clone", "https://github.com/KhronosGroup/SPIRV-Headers.git", "spirv-tools/external/spirv-headers"
-];
-const CMD_CLONE_EFFCEE: [&'static str; 4] = [
-    "git", "clone", "https://github.com/google/effcee.git", "spirv-tools/external/effcee"
-];
-const CMD_CLONE_RE2: [&'static str; 4] = [
-    "git", "clone", "https://github.com/google/re2.git", "spirv-tools/external/re2"
+const SPIRV_TOOLS_DIR: &str = "spirv-tools";
+const SPIRV_HEADERS_URL: &str = "https://github.com/KhronosGroup/SPIRV-Headers.git";
+
+/// Oldest SPIRV-Tools release the bindings in `src/raw/bindings.rs` are written against
+const SPIRV_TOOLS_MIN_VERSION: (u32, u32) = (2022, 1);
+
+/// Newest declarations in `libspirv.h` the bindings use, which installations
+/// without a pkg-config file are checked for instead of a version
+const REQUIRED_SYMBOLS: &[&str] = &[
+    "SPV_ENV_UNIVERSAL_1_6",
+    "SPV_ENV_VULKAN_1_3",
+    "spvValidatorOptionsSetAllowLocalSizeId",
+    "spvValidatorOptionsSetFriendlyNames",
+    "spvValidatorOptionsSetWorkgroupScalarBlockLayout"
 ];
 
 /// Describes where in the build process an error occured
-#[derive(Debug)]
 enum BuildError {
-    CloneDependencies(&'static str),
-    ConfigureProject,
-    BuildProject
+    CloneDependencies(String),
+    #[cfg_attr(not(feature = "generate-bindings"), allow(dead_code))]
+    GenerateBindings(String),
+    MissingSource(String),
+    SystemLibrary(String)
 }
 
-/// Check for and build spirv-tools, then configure the compiler to 
+// Debug is what cargo prints when `main` fails, so keep it readable
+impl fmt::Debug for BuildError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            BuildError::CloneDependencies(msg) => write!(f, "{}", msg),
+            BuildError::GenerateBindings(msg)  => write!(f, "{}", msg),
+            BuildError::MissingSource(msg)     => write!(f, "{}", msg),
+            BuildError::SystemLibrary(msg)     => write!(f, "{}", msg)
+        }
+    }
+}
+
+/// Locations of the sources needed to build spirv-tools
+struct Sources {
+    tools: PathBuf,
+    headers: PathBuf
+}
+
+/// Check for and build spirv-tools, then configure the compiler to link against it
 fn main() -> Result<(), BuildError> {
-    clone_dependencies()?;
-    configure_project()?;
-    build_project()?;
+    println!("cargo:rerun-if-changed=build.rs");
+    println!("cargo:rerun-if-changed=src/c/shim.cpp");
+    println!("cargo:rerun-if-changed=src/c/probe.c");
+    println!("cargo:rerun-if-env-changed=CXXSTDLIB");
+    println!("cargo:rerun-if-env-changed=SPIRV_TOOLS_SOURCE_DIR");
+    println!("cargo:rerun-if-env-changed=SPIRV_HEADERS_SOURCE_DIR");
+    println!("cargo:rerun-if-env-changed=SPIRV_TOOLS_DIR");
+    println!("cargo:rerun-if-env-changed=SPIRV_TOOLS_LIB_DIR");
+    println!("cargo:rerun-if-env-changed=SPIRV_TOOLS_INCLUDE_DIR");
+    println!("cargo:rerun-if-env-changed=SPIRV_TOOLS_STATIC");
+
+    let include_dirs = match link_system_library()? {
+        Some(include_dirs) => include_dirs,
+        None               => {
+            let sources = locate_sources()?;
+
+            // Cargo checks directories recursively, so this covers edits to the
+            // sources as well as the external dependencies inside them
+            println!("cargo:rerun-if-changed={}", sources.tools.display());
+            println!("cargo:rerun-if-changed={}", sources.headers.display());
+
+            let install_dir = build_project(&sources);
+
+            emit_source_commit(&sources.tools);
+
+            link_libraries(&install_dir.join("lib"), true);
+
+            vec![install_dir.join("include")]
+        }
+    };
+
+    build_shim(&include_dirs);
+    build_binding_probe(&include_dirs);
+    generate_bindings(&include_dirs)
+}
+
+/// Link against an existing installation of spirv-tools if one was requested.
+///
+/// `SPIRV_TOOLS_LIB_DIR` names the directory containing the libraries and
+/// `SPIRV_TOOLS_DIR` the install prefix. Otherwise the `system` feature looks
+/// the installation up with pkg-config. `SPIRV_TOOLS_INCLUDE_DIR` overrides the
+/// headers of the first two. Returns the include directories of the
+/// installation, or `None` if the bundled sources should be built instead.
+fn link_system_library() -> Result<Option<Vec<PathBuf>>, BuildError> {
+    let statik = link_static();
+
+    if let Some(lib_dir) = env_path("SPIRV_TOOLS_LIB_DIR") {
+        // Library directories such as `/usr/lib/x86_64-linux-gnu` don't sit next
+        // to the headers, so their location has to be given or read from the
+        // pkg-config file
+        let include_dir = env_path("SPIRV_TOOLS_INCLUDE_DIR")
+            .or_else(|| pkg_config_include_dir(&pkg_config_file(&lib_dir)))
+            .ok_or_else(|| BuildError::SystemLibrary(format!(
+                "Could not find the SPIRV-Tools headers for '{}', set SPIRV_TOOLS_INCLUDE_DIR to the directory containing `spirv-tools/libspirv.h`",
+                lib_dir.display()
+            )))?;
+
+        check_installation(&lib_dir, &include_dir)?;
+
+        let include_dirs = vec![include_dir];
+        check_cpp_headers(&include_dirs)?;
+
+        link_libraries(&lib_dir, statik);
+        return Ok(Some(include_dirs));
+    }
+
+    if let Some(prefix) = env_path("SPIRV_TOOLS_DIR") {
+        let lib_dir = ["lib", "lib64"].iter()
+            .map(|x| prefix.join(x))
+            .find(|x| pkg_config_file(x).is_file())
+            .unwrap_or_else(|| prefix.join("lib"));
+
+        let include_dir = env_path("SPIRV_TOOLS_INCLUDE_DIR")
+            .unwrap_or_else(|| prefix.join("include"));
+
+        check_installation(&lib_dir, &include_dir)?;
+
+        let include_dirs = vec![include_dir];
+        check_cpp_headers(&include_dirs)?;
+
+        link_libraries(&lib_dir, statik);
+        return Ok(Some(include_dirs));
+    }
+
+    if env::var_os("CARGO_FEATURE_SYSTEM").is_some() {
+        return probe_pkg_config(statik).map(Some);
+    }
+
+    Ok(None)
+}
 
-    // Point the compiler at the built library
-    let current_dir = env::current_dir().unwrap();
-    println!("cargo:rustc-link-search=native={}\\spirv-tools\\build\\source\\Release", current_dir.display());
-    println!("cargo:rustc-link-search=native={}\\spirv-tools\\build\\source\\opt\\Release", current_dir.display());
-    println!("cargo:rustc-link-lib=static=SPIRV-Tools");
-    println!("cargo:rustc-link-lib=static=SPIRV-Tools-opt");
+/// Find and link spirv-tools with pkg-config, which emits the link flags itself.
+/// Returns the include directories reported by pkg-config.
+#[cfg(feature = "system")]
+fn probe_pkg_config(statik: bool) -> Result<Vec<PathBuf>, BuildError> {
+    let min_version = format!("{}.{}", SPIRV_TOOLS_MIN_VERSION.0, SPIRV_TOOLS_MIN_VERSION.1);
+
+    let library = pkg_config::Config::new()
+        .atleast_version(&min_version)
+        .statik(statik)
+        .probe("SPIRV-Tools")
+        .map_err(|e| BuildError::SystemLibrary(format!("Failed to find SPIRV-Tools with pkg-config: {}", e)))?;
+
+    if statik {
+        link_cpp_stdlib();
+    }
+
+    check_cpp_headers(&library.include_paths)?;
+    Ok(library.include_paths)
+}
+
+#[cfg(not(feature = "system"))]
+fn probe_pkg_config(_statik: bool) -> Result<Vec<PathBuf>, BuildError> {
+    unreachable!("pkg-config is only probed with the `system` feature enabled")
+}
+
+/// Whether an installed spirv-tools should be linked statically. Defaults to
+/// dynamic linking, set `SPIRV_TOOLS_STATIC=1` to link the static libraries.
+fn link_static() -> bool {
+    match env::var("SPIRV_TOOLS_STATIC") {
+        Ok(x)  => x == "1" || x.eq_ignore_ascii_case("true"),
+        Err(_) => false
+    }
+}
+
+/// Check that the installation with libraries in `lib_dir` is new enough.
+///
+/// The version is read from `<lib_dir>/pkgconfig/SPIRV-Tools.pc` if it exists.
+/// Installations without a pkg-config file are checked by looking for the newest
+/// symbols the bindings use in their headers.
+fn check_installation(lib_dir: &Path, include_dir: &Path) -> Result<(), BuildError> {
+    if let Some(version) = pkg_config_version(&pkg_config_file(lib_dir)) {
+        return check_version(&version);
+    }
+
+    let header = include_dir.join("spirv-tools").join("libspirv.h");
+    let contents = fs::read_to_string(&header)
+        .map_err(|_| BuildError::SystemLibrary(format!(
+            "Could not determine the version of SPIRV-Tools in '{}', neither '{}' nor '{}' exist",
+            lib_dir.display(),
+            pkg_config_file(lib_dir).display(),
+            header.display()
+        )))?;
+
+    let missing: Vec<_> = REQUIRED_SYMBOLS.iter()
+        .filter(|x| !contents.contains(*x))
+        .collect();
+
+    if !missing.is_empty() {
+        return Err(BuildError::SystemLibrary(format!(
+            "'{}' is missing {:?}, at least SPIRV-Tools v{}.{} is required",
+            header.display(),
+            missing,
+            SPIRV_TOOLS_MIN_VERSION.0,
+            SPIRV_TOOLS_MIN_VERSION.1
+        )));
+    }
 
     Ok(())
 }
 
-/// Clone any missing dependencies
-fn clone_dependencies() -> Result<(), BuildError> {
-    let path = PathBuf::from("./spirv-tools/external/spirv-headers");
-    if !path.exists() {
-        Command::new("cmd")
-            .arg("/C")
-            .args(&CMD_CLONE_SPIRV_HEADERS)
-            .stdout(Stdio::inherit())
-            .output()
-            .map_err(|_| BuildError::CloneDependencies("Failed to clone spirv-headers"))?;
+/// Check that the C++ headers the shim in `src/c/shim.cpp` is compiled against
+/// are installed. Only the C++ API of spirv-tools is used for the optimizer, as
+/// the C optimizer interfaces of forks and newer releases are incompatible.
+fn check_cpp_headers(include_dirs: &[PathBuf]) -> Result<(), BuildError> {
+    for header in ["libspirv.hpp", "optimizer.hpp"] {
+        let found = include_dirs.iter()
+            .any(|x| x.join("spirv-tools").join(header).is_file());
+
+        if !found {
+            return Err(BuildError::SystemLibrary(format!(
+                "The SPIRV-Tools C++ header `spirv-tools/{}` was not found in any of {:?}",
+                header,
+                include_dirs
+            )));
+        }
     }
 
-    let path = PathBuf::from("./spirv-tools/external/effcee");
-    if !path.exists() {
-        Command::new("cmd")
-            .arg("/C")
-            .args(&CMD_CLONE_EFFCEE)
-            .stdout(Stdio::inherit())
-            .output()
-            .map_err(|_| BuildError::CloneDependencies("Failed to clone effcee"))?;
+    Ok(())
+}
+
+/// The pkg-config file of an installation with libraries in `lib_dir`
+fn pkg_config_file(lib_dir: &Path) -> PathBuf {
+    lib_dir.join("pkgconfig").join("SPIRV-Tools.pc")
+}
+
+/// Read the `Version` field out of a pkg-config file
+fn pkg_config_version(path: &Path) -> Option<String> {
+    fs::read_to_string(path)
+        .ok()?
+        .lines()
+        .find_map(|x| x.strip_prefix("Version:"))
+        .map(|x| x.trim().to_owned())
+}
+
+/// Read the `includedir` variable out of a pkg-config file, expanding the
+/// variables it refers to
+fn pkg_config_include_dir(path: &Path) -> Option<PathBuf> {
+    let contents = fs::read_to_string(path).ok()?;
+
+    // Relocatable files refer to their own directory
+    let mut variables = vec![(
+        "pcfiledir".to_owned(),
+        path.parent()?.to_string_lossy().into_owned()
+    )];
+
+    for line in contents.lines() {
+        // Fields such as `Cflags: -I${includedir}` are not variables
+        let (name, value) = match line.split_once('=') {
+            Some((name, value)) if !name.contains(':') => (name.trim(), value.trim()),
+            _                                          => continue
+        };
+
+        let mut expanded = value.to_owned();
+        for (variable, value) in variables.iter().rev() {
+            expanded = expanded.replace(&format!("${{{}}}", variable), value);
+        }
+
+        variables.push((name.to_owned(), expanded));
     }
 
-    let path = PathBuf::from("./spirv-tools/external/re2");
-    if !path.exists() {
-        Command::new("cmd")
-            .arg("/C")
-            .args(&CMD_CLONE_RE2)
-            .stdout(Stdio::inherit())
-            .output()
-            .map_err(|_| BuildError::CloneDependencies("Failed to clone re2"))?;
+    variables.into_iter()
+        .rev()
+        .find(|(name, _)| name == "includedir")
+        .map(|(_, value)| PathBuf::from(value))
+}
+
+/// Check that an installed version string such as `2020.1.1` is new enough
+fn check_version(version: &str) -> Result<(), BuildError> {
+    let mut parts = version.split('.')
+        .map(|x| x.parse::<u32>().ok());
+
+    let parsed = match (parts.next().flatten(), parts.next().flatten()) {
+        (Some(year), Some(minor)) => (year, minor),
+        _                         => return Err(BuildError::SystemLibrary(format!(
+            "Failed to parse the SPIRV-Tools version '{}'",
+            version
+        )))
+    };
+
+    if parsed < SPIRV_TOOLS_MIN_VERSION {
+        return Err(BuildError::SystemLibrary(format!(
+            "Found SPIRV-Tools v{}, but at least v{}.{} is required",
+            version,
+            SPIRV_TOOLS_MIN_VERSION.0,
+            SPIRV_TOOLS_MIN_VERSION.1
+        )));
     }
 
     Ok(())
 }
 
-/// Configure CMake for building
-fn configure_project() -> Result<(), BuildError> {
-    Command::new("cmd")
-        .arg("/C")
-        .args(&["mkdir", "spirv-tools/build", "&", "cd", "spirv-tools/build", "&&", "cmake", "../"])
-        .stdout(Stdio::inherit())
-        .output()
-        .map_err(|_| BuildError::ConfigureProject)?;
+/// Point the compiler at the spirv-tools libraries in `lib_dir`
+fn link_libraries(lib_dir: &Path, statik: bool) {
+    let kind = if statik { "static" } else { "dylib" };
+
+    // The opt library depends on the core library, so it has to come first for
+    // single pass linkers.
+    println!("cargo:rustc-link-search=native={}", lib_dir.display());
+    println!("cargo:rustc-link-lib={}=SPIRV-Tools-opt", kind);
+    println!("cargo:rustc-link-lib={}=SPIRV-Tools", kind);
+
+    // Shared libraries already carry their C++ runtime dependency
+    if statik {
+        link_cpp_stdlib();
+    }
+}
+
+/// Find the spirv-tools and spirv-headers sources.
+///
+/// Both default to the copies inside the `spirv-tools` submodule and can be
+/// pointed at a local checkout with `SPIRV_TOOLS_SOURCE_DIR` and
+/// `SPIRV_HEADERS_SOURCE_DIR`. Missing spirv-headers are cloned from github
+/// unless the `vendored` feature is enabled, in which case the network is never
+/// touched and missing sources are an error.
+fn locate_sources() -> Result<Sources, BuildError> {
+    let manifest_dir = PathBuf::from(env::var_os("CARGO_MANIFEST_DIR").unwrap());
+    let vendored = env::var_os("CARGO_FEATURE_VENDORED").is_some();
+
+    let tools = env_path("SPIRV_TOOLS_SOURCE_DIR")
+        .unwrap_or_else(|| manifest_dir.join(SPIRV_TOOLS_DIR));
+
+    if !tools.join("CMakeLists.txt").is_file() {
+        return Err(BuildError::MissingSource(format!(
+            "SPIRV-Tools sources not found at '{}'. Initialize the `spirv-tools` submodule or set SPIRV_TOOLS_SOURCE_DIR to a local checkout",
+            tools.display()
+        )));
+    }
+
+    let (headers, is_local) = match env_path("SPIRV_HEADERS_SOURCE_DIR") {
+        Some(path) => (path, true),
+        None       => (tools.join("external").join("spirv-headers"), false)
+    };
+
+    if !has_spirv_headers(&headers) {
+        if vendored || is_local {
+            return Err(BuildError::MissingSource(format!(
+                "SPIRV-Headers sources not found at '{}'. Vendored builds never download dependencies, set SPIRV_HEADERS_SOURCE_DIR to a local checkout",
+                headers.display()
+            )));
+        }
+
+        clone_spirv_headers(&headers)?;
+    }
+
+    Ok(Sources { tools, headers })
+}
+
+/// Read a path from an environment variable, ignoring empty values
+fn env_path(key: &str) -> Option<PathBuf> {
+    env::var_os(key)
+        .filter(|x| !x.is_empty())
+        .map(PathBuf::from)
+}
+
+/// Check that a directory contains a usable copy of spirv-headers
+fn has_spirv_headers(path: &Path) -> bool {
+    path.join("include")
+        .join("spirv")
+        .join("unified1")
+        .join("spirv.core.grammar.json")
+        .is_file()
+}
+
+/// Clone spirv-headers into the given directory
+fn clone_spirv_headers(path: &Path) -> Result<(), BuildError> {
+    let err = || BuildError::CloneDependencies(format!(
+        "Failed to clone spirv-headers into '{}'",
+        path.display()
+    ));
+
+    let status = Command::new("git")
+        .args(["clone", "--depth", "1", SPIRV_HEADERS_URL])
+        .arg(path)
+        .status()
+        .map_err(|_| err())?;
+
+    if !status.success() {
+        return Err(err());
+    }
 
     Ok(())
 }
 
-/// Invoke CMake and build the project
-fn build_project() -> Result<(), BuildError> {
-    Command::new("cmd")
-        .arg("/C")
-        .args(&["cmake", "--build", "./spirv-tools/build", "--config", "Release"])
-        .stdout(Stdio::inherit())
+/// Expose the commit of the spirv-tools sources being built to the crate as
+/// `SPIRV_TOOLS_COMMIT`. Nothing is emitted if the sources are not the root of a
+/// git checkout, such as when they are unpacked from the published crate or
+/// inside another repository.
+fn emit_source_commit(tools: &Path) {
+    let toplevel = git(tools, &["rev-parse", "--show-toplevel"])
+        .and_then(|x| fs::canonicalize(x).ok());
+
+    if toplevel.is_none() || toplevel != fs::canonicalize(tools).ok() {
+        return;
+    }
+
+    let commit = match git(tools, &["rev-parse", "HEAD"]) {
+        Some(commit) => commit,
+        None         => return
+    };
+
+    // The git directory lives outside of submodules, so HEAD and the branch it
+    // points to are watched separately to pick up a checkout of another commit
+    let mut watched = vec!["HEAD".to_owned()];
+    if let Some(branch) = git(tools, &["symbolic-ref", "-q", "HEAD"]) {
+        watched.push(branch);
+    }
+
+    for name in watched {
+        // Relative paths are relative to the directory git ran in
+        if let Some(path) = git(tools, &["rev-parse", "--git-path", &name]) {
+            println!("cargo:rerun-if-changed={}", tools.join(path).display());
+        }
+    }
+
+    println!("cargo:rustc-env=SPIRV_TOOLS_COMMIT={}", commit);
+}
+
+/// Run a git command in `dir`, returning the trimmed output if it succeeded and
+/// printed anything
+fn git(dir: &Path, args: &[&str]) -> Option<String> {
+    let output = Command::new("git")
+        .arg("-C")
+        .arg(dir)
+        .args(args)
         .output()
-        .map_err(|_| BuildError::BuildProject)?;
+        .ok()?;
+
+    let stdout = String::from_utf8_lossy(&output.stdout).trim().to_owned();
+    if output.status.success() && !stdout.is_empty() {
+        Some(stdout)
+    }
+    else {
+        None
+    }
+}
+
+/// Configure and build the project with CMake, returning the install directory
+fn build_project(sources: &Sources) -> PathBuf {
+    // Libraries are installed into `<out>/lib`, which avoids guessing at the per
+    // configuration subdirectories used by multi-config generators such as MSVC.
+    //
+    // Tests are skipped, so effcee and re2 are never needed.
+    cmake::Config::new(&sources.tools)
+        .profile("Release")
+        .define("CMAKE_INSTALL_LIBDIR", "lib")
+        .define("SPIRV-Headers_SOURCE_DIR", &sources.headers)
+        .define("SPIRV_SKIP_TESTS", "ON")
+        .define("SPIRV_SKIP_EXECUTABLES", "ON")
+        .define("SPIRV_WERROR", "OFF")
+        .build()
+}
+
+/// Compile the C interface for the message consumers and the optimizer, which
+/// spirv-tools only provides in C++
+fn build_shim(include_dirs: &[PathBuf]) {
+    let stdlib = cpp_stdlib();
+
+    cc::Build::new()
+        .cpp(true)
+        .cpp_link_stdlib(stdlib.as_deref())
+        .flag_if_supported("-std=c++11")
+        .includes(include_dirs)
+        .file("src/c/shim.cpp")
+        .compile("spirv-tools-rs-shim");
+}
+
+/// Compile the table of values and layouts from `libspirv.h` that the tests
+/// check the bindings against.
+///
+/// Only `tests/raw.rs` links the table, so it is kept out of the library and
+/// just put next to it in the output directory.
+fn build_binding_probe(include_dirs: &[PathBuf]) {
+    cc::Build::new()
+        .cargo_metadata(false)
+        .includes(include_dirs)
+        .file("src/c/probe.c")
+        .compile("spirv-tools-rs-probe");
 
+    println!("cargo:rustc-link-search=native={}", env::var("OUT_DIR").unwrap());
+}
+
+/// Generate bindings for `libspirv.h` into `$OUT_DIR/raw.rs`, which replace the
+/// checked in ones in `src/raw/bindings.rs`.
+///
+/// Enum variants are stripped of their C prefixes, so `SPV_ENV_VULKAN_1_1`
+/// becomes `spv_target_env::VULKAN_1_1`, matching the checked in bindings.
+#[cfg(feature = "generate-bindings")]
+fn generate_bindings(include_dirs: &[PathBuf]) -> Result<(), BuildError> {
+    let header = include_dirs.iter()
+        .map(|x| x.join("spirv-tools").join("libspirv.h"))
+        .find(|x| x.is_file())
+        .ok_or_else(|| BuildError::GenerateBindings(format!(
+            "Could not find `spirv-tools/libspirv.h` in any of {:?}",
+            include_dirs
+        )))?;
+
+    println!("cargo:rerun-if-changed={}", header.display());
+
+    let bindings = bindgen::Builder::default()
+        .header(header.to_string_lossy())
+        .clang_args(include_dirs.iter().map(|x| format!("-I{}", x.display())))
+        .allowlist_function("spv.*")
+        .allowlist_type("spv_.*")
+        .allowlist_var("kDefaultMaxIdBound")
+        .newtype_enum("spv_.*")
+        .derive_eq(true)
+        .derive_hash(true)
+        .parse_callbacks(Box::new(StripEnumPrefix))
+        .generate()
+        .map_err(|e| BuildError::GenerateBindings(format!("Failed to generate bindings for '{}': {}", header.display(), e)))?;
+
+    let out_path = PathBuf::from(env::var_os("OUT_DIR").unwrap()).join("raw.rs");
+    bindings.write_to_file(&out_path)
+        .map_err(|e| BuildError::GenerateBindings(format!("Failed to write bindings to '{}': {}", out_path.display(), e)))
+}
+
+#[cfg(not(feature = "generate-bindings"))]
+fn generate_bindings(_include_dirs: &[PathBuf]) -> Result<(), BuildError> {
     Ok(())
 }
+
+/// Strips the C prefixes from enum variant names
+#[cfg(feature = "generate-bindings")]
+#[derive(Debug)]
+struct StripEnumPrefix;
+
+#[cfg(feature = "generate-bindings")]
+impl bindgen::callbacks::ParseCallbacks for StripEnumPrefix {
+    fn enum_variant_name(
+        &self,
+        _enum_name: Option<&str>,
+        original_variant_name: &str,
+        _variant_value: bindgen::callbacks::EnumVariantValue
+    ) -> Option<String> {
+        // Ordered so that longer prefixes are tried first
+        const PREFIXES: &[&str] = &[
+            "SPV_TEXT_TO_BINARY_OPTION_",
+            "SPV_BINARY_TO_TEXT_OPTION_",
+            "SPV_OPERAND_TYPE_",
+            "SPV_EXT_INST_TYPE_",
+            "SPV_ENDIANNESS_",
+            "SPV_NUMBER_",
+            "SPV_MSG_",
+            "SPV_ENV_",
+            "spv_validator_limit_",
+            "SPV_",
+        ];
+
+        PREFIXES.iter()
+            .find_map(|x| original_variant_name.strip_prefix(x))
+            .map(|x| x.to_owned())
+    }
+}
+
+/// Link the C++ standard library needed by the static spirv-tools libraries
+fn link_cpp_stdlib() {
+    if let Some(stdlib) = cpp_stdlib() {
+        println!("cargo:rustc-link-lib={}", stdlib);
+    }
+}
+
+/// Select the C++ standard library to link against for the target toolchain.
+///
+/// Can be overridden with the `CXXSTDLIB` environment variable, an empty value
+/// disables linking a standard library entirely.
+fn cpp_stdlib() -> Option<String> {
+    if let Ok(stdlib) = env::var("CXXSTDLIB") {
+        return if stdlib.is_empty() { None } else { Some(stdlib) };
+    }
+
+    let target = env::var("TARGET").unwrap_or_default();
+    if target.contains("msvc") {
+        None
+    }
+    else if target.contains("apple") || target.contains("freebsd") || target.contains("openbsd") {
+        Some("c++".to_owned())
+    }
+    else if target.contains("android") {
+        Some("c++_shared".to_owned())
+    }
+    else {
+        Some("stdc++".to_owned())
+    }
+}
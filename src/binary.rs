@@ -0,0 +1,125 @@
+use std::convert::TryFrom;
+use std::fs;
+use std::ops::Deref;
+use std::path::Path;
+use std::sync::Arc;
+
+use crate::error::{BinaryError, HeaderError};
+use crate::header::{ModuleHeader, MAGIC_NUMBER};
+use crate::parse::Endianness;
+
+/// A SPIR-V module in native endianness, read from bytes of either byte order
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct SpirvBinary {
+    words: Vec<u32>,
+    endianness: Endianness
+}
+
+impl SpirvBinary {
+    /// Convert a byte buffer, such as the contents of a `.spv` file, into words.
+    ///
+    /// The byte order is detected from the magic number and the words swapped to
+    /// native endianness if needed. The buffer does not need to be aligned.
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self, BinaryError> {
+        let chunks = bytes.chunks_exact(4);
+        if !chunks.remainder().is_empty() {
+            return Err(BinaryError::InvalidLength(bytes.len()));
+        }
+
+        let magic = match bytes.get(..4) {
+            Some(x) => word_bytes(x),
+            None    => return Err(HeaderError::Truncated(0).into())
+        };
+
+        let (endianness, decode): (_, fn([u8; 4]) -> u32) = if u32::from_le_bytes(magic) == MAGIC_NUMBER {
+            (Endianness::Little, u32::from_le_bytes)
+        }
+        else if u32::from_be_bytes(magic) == MAGIC_NUMBER {
+            (Endianness::Big, u32::from_be_bytes)
+        }
+        else {
+            return Err(HeaderError::BadMagic(u32::from_ne_bytes(magic)).into());
+        };
+
+        let words: Vec<u32> = chunks
+            .map(|x| decode(word_bytes(x)))
+            .collect();
+
+        ModuleHeader::parse(&words)?;
+
+        Ok(Self { words, endianness })
+    }
+
+    /// Read a binary from a file
+    pub fn read<P: AsRef<Path>>(path: P) -> Result<Self, BinaryError> {
+        let bytes = fs::read(path)
+            .map_err(|e| BinaryError::Io(Arc::new(e)))?;
+
+        Self::from_bytes(&bytes)
+    }
+
+    /// The byte order the binary was stored in before it was converted
+    pub fn endianness(&self) -> Endianness {
+        self.endianness
+    }
+
+    /// The header of the module, decoded from the native endian words
+    pub fn header(&self) -> ModuleHeader {
+        ModuleHeader::parse(&self.words)
+            .expect("Header is checked on creation")
+    }
+
+    /// The words of the module
+    pub fn words(&self) -> &[u32] {
+        &self.words
+    }
+
+    /// Convert the binary into it's words
+    pub fn into_words(self) -> Vec<u32> {
+        self.words
+    }
+}
+
+impl TryFrom<&[u8]> for SpirvBinary {
+    type Error = BinaryError;
+
+    fn try_from(bytes: &[u8]) -> Result<Self, Self::Error> {
+        Self::from_bytes(bytes)
+    }
+}
+
+impl TryFrom<Vec<u32>> for SpirvBinary {
+    type Error = BinaryError;
+
+    /// Wrap words read in either byte order, swapping them to native endianness
+    /// if needed
+    fn try_from(mut words: Vec<u32>) -> Result<Self, Self::Error> {
+        let endianness = ModuleHeader::parse(&words)?.endianness;
+        if endianness != Endianness::NATIVE {
+            for word in &mut words {
+                *word = word.swap_bytes();
+            }
+        }
+
+        Ok(Self { words, endianness })
+    }
+}
+
+impl Deref for SpirvBinary {
+    type Target = [u32];
+
+    fn deref(&self) -> &[u32] {
+        &self.words
+    }
+}
+
+impl AsRef<[u32]> for SpirvBinary {
+    fn as_ref(&self) -> &[u32] {
+        &self.words
+    }
+}
+
+/// Copy a four byte chunk into an array
+fn word_bytes(bytes: &[u8]) -> [u8; 4] {
+    [bytes[0], bytes[1], bytes[2], bytes[3]]
+}
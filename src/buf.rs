@@ -0,0 +1,196 @@
+use std::fmt::{self, Debug, Display};
+use std::ops::Deref;
+use std::slice;
+use std::str;
+
+use crate::raw::*;
+
+/// A binary owned by spirv-tools, such as the output of the assembler or the
+/// optimizer. The words are used in place and freed when the buffer is dropped.
+pub struct SpvBinaryBuf {
+    raw: spv_binary
+}
+
+// The buffer is uniquely owned and never mutated after it is created, so it can
+// be moved to and read from other threads
+unsafe impl Send for SpvBinaryBuf {}
+unsafe impl Sync for SpvBinaryBuf {}
+
+impl SpvBinaryBuf {
+    /// Take ownership of an `spv_binary` returned from spirv-tools
+    ///
+    /// # Safety
+    /// `raw` has to be a valid binary that is not destroyed elsewhere
+    pub(crate) unsafe fn from_raw(raw: spv_binary) -> Self {
+        assert!(!raw.is_null(), "Spirv Tools returned a null binary!");
+
+        Self { raw }
+    }
+
+    /// The words of the binary
+    pub fn words(&self) -> &[u32] {
+        unsafe {
+            if (*self.raw).code.is_null() {
+                &[]
+            }
+            else {
+                slice::from_raw_parts((*self.raw).code, (*self.raw).wordCount)
+            }
+        }
+    }
+
+    /// Copy the words into a `Vec`, freeing the buffer
+    pub fn into_vec(self) -> Vec<u32> {
+        self.words().to_vec()
+    }
+}
+
+impl Deref for SpvBinaryBuf {
+    type Target = [u32];
+
+    fn deref(&self) -> &[u32] {
+        self.words()
+    }
+}
+
+impl AsRef<[u32]> for SpvBinaryBuf {
+    fn as_ref(&self) -> &[u32] {
+        self.words()
+    }
+}
+
+impl Debug for SpvBinaryBuf {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_list()
+            .entries(self.words())
+            .finish()
+    }
+}
+
+impl PartialEq for SpvBinaryBuf {
+    fn eq(&self, other: &Self) -> bool {
+        self.words() == other.words()
+    }
+}
+
+impl Eq for SpvBinaryBuf {}
+
+impl From<SpvBinaryBuf> for Vec<u32> {
+    fn from(buf: SpvBinaryBuf) -> Self {
+        buf.into_vec()
+    }
+}
+
+impl Drop for SpvBinaryBuf {
+    fn drop(&mut self) {
+        unsafe { spvBinaryDestroy(self.raw); }
+    }
+}
+
+/// Text owned by spirv-tools, such as the output of the disassembler. The text is
+/// used in place and freed when the buffer is dropped.
+pub struct SpvTextBuf {
+    raw: spv_text
+}
+
+// The buffer is uniquely owned and never mutated after it is created, so it can
+// be moved to and read from other threads
+unsafe impl Send for SpvTextBuf {}
+unsafe impl Sync for SpvTextBuf {}
+
+impl SpvTextBuf {
+    /// Take ownership of an `spv_text` returned from spirv-tools
+    ///
+    /// # Safety
+    /// `raw` has to be valid text that is not destroyed elsewhere
+    pub(crate) unsafe fn from_raw(raw: spv_text) -> Self {
+        assert!(!raw.is_null(), "Spirv Tools returned null text!");
+
+        let text = Self { raw };
+        str::from_utf8(text.bytes())
+            .expect("Spirv Tools returned an invalid encoding!");
+
+        text
+    }
+
+    /// The text as a string slice
+    pub fn as_str(&self) -> &str {
+        // The encoding is checked on creation
+        unsafe { str::from_utf8_unchecked(self.bytes()) }
+    }
+
+    /// Copy the text into a `String`, freeing the buffer
+    pub fn into_string(self) -> String {
+        self.as_str().to_owned()
+    }
+
+    /// The raw bytes of the text
+    fn bytes(&self) -> &[u8] {
+        unsafe {
+            if (*self.raw).str_.is_null() {
+                &[]
+            }
+            else {
+                slice::from_raw_parts((*self.raw).str_ as *const u8, (*self.raw).length)
+            }
+        }
+    }
+}
+
+impl Deref for SpvTextBuf {
+    type Target = str;
+
+    fn deref(&self) -> &str {
+        self.as_str()
+    }
+}
+
+impl AsRef<str> for SpvTextBuf {
+    fn as_ref(&self) -> &str {
+        self.as_str()
+    }
+}
+
+impl Debug for SpvTextBuf {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        Debug::fmt(self.as_str(), f)
+    }
+}
+
+impl Display for SpvTextBuf {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl PartialEq for SpvTextBuf {
+    fn eq(&self, other: &Self) -> bool {
+        self.as_str() == other.as_str()
+    }
+}
+
+impl Eq for SpvTextBuf {}
+
+impl PartialEq<str> for SpvTextBuf {
+    fn eq(&self, other: &str) -> bool {
+        self.as_str() == other
+    }
+}
+
+impl PartialEq<&str> for SpvTextBuf {
+    fn eq(&self, other: &&str) -> bool {
+        self.as_str() == *other
+    }
+}
+
+impl From<SpvTextBuf> for String {
+    fn from(buf: SpvTextBuf) -> Self {
+        buf.into_string()
+    }
+}
+
+impl Drop for SpvTextBuf {
+    fn drop(&mut self) {
+        unsafe { spvTextDestroy(self.raw); }
+    }
+}
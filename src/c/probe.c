@@ -0,0 +1,115 @@
+// Values and layouts of the spirv-tools C interface as the C compiler sees them.
+// `tests/raw.rs` compares them against the Rust bindings, so the tests fail if
+// the bindings drift from the headers being built against.
+
+#include <stddef.h>
+
+#include "spirv-tools/libspirv.h"
+
+typedef struct spvrs_probe {
+  const char* name;
+  long long value;
+} spvrs_probe;
+
+#define VALUE(x) {#x, (long long)(x)}
+#define SIZE(t) {"sizeof(" #t ")", (long long)sizeof(t)}
+#define ALIGN(t) \
+  {"alignof(" #t ")", (long long)offsetof(struct { char c; t x; }, x)}
+#define OFFSET(t, f) {"offsetof(" #t ", " #f ")", (long long)offsetof(t, f)}
+
+const spvrs_probe spvrs_probes[] = {
+    VALUE(SPV_SUCCESS),
+    VALUE(SPV_REQUESTED_TERMINATION),
+    VALUE(SPV_ERROR_INTERNAL),
+    VALUE(SPV_ERROR_INVALID_BINARY),
+    VALUE(SPV_ERROR_INVALID_DATA),
+    VALUE(SPV_ERROR_WRONG_VERSION),
+
+    VALUE(SPV_MSG_FATAL),
+    VALUE(SPV_MSG_DEBUG),
+    VALUE(SPV_ENDIANNESS_BIG),
+    VALUE(SPV_OPERAND_TYPE_LITERAL_INTEGER),
+    VALUE(SPV_OPERAND_TYPE_MEMORY_ACCESS),
+    VALUE(SPV_OPERAND_TYPE_FRAGMENT_SHADING_RATE),
+    VALUE(SPV_OPERAND_TYPE_OPTIONAL_CIV),
+    VALUE(SPV_OPERAND_TYPE_VARIABLE_ID),
+    VALUE(SPV_OPERAND_TYPE_CLDEBUG100_DEBUG_IMPORTED_ENTITY),
+    VALUE(SPV_OPERAND_TYPE_OPTIONAL_PACKED_VECTOR_FORMAT),
+    VALUE(SPV_OPERAND_TYPE_NUM_OPERAND_TYPES),
+    VALUE(SPV_EXT_INST_TYPE_DEBUGINFO),
+    VALUE(SPV_EXT_INST_TYPE_NONSEMANTIC_CLSPVREFLECTION),
+    VALUE(SPV_EXT_INST_TYPE_NONSEMANTIC_SHADER_DEBUGINFO_100),
+    VALUE(SPV_EXT_INST_TYPE_NONSEMANTIC_UNKNOWN),
+    VALUE(SPV_NUMBER_FLOATING),
+
+    VALUE(SPV_TEXT_TO_BINARY_OPTION_PRESERVE_NUMERIC_IDS),
+    VALUE(SPV_BINARY_TO_TEXT_OPTION_PRINT),
+    VALUE(SPV_BINARY_TO_TEXT_OPTION_FRIENDLY_NAMES),
+    VALUE(kDefaultMaxIdBound),
+
+    VALUE(SPV_ENV_UNIVERSAL_1_0),
+    VALUE(SPV_ENV_VULKAN_1_0),
+    VALUE(SPV_ENV_UNIVERSAL_1_1),
+    VALUE(SPV_ENV_OPENCL_2_1),
+    VALUE(SPV_ENV_OPENCL_2_2),
+    VALUE(SPV_ENV_OPENGL_4_0),
+    VALUE(SPV_ENV_OPENGL_4_1),
+    VALUE(SPV_ENV_OPENGL_4_2),
+    VALUE(SPV_ENV_OPENGL_4_3),
+    VALUE(SPV_ENV_OPENGL_4_5),
+    VALUE(SPV_ENV_UNIVERSAL_1_2),
+    VALUE(SPV_ENV_OPENCL_1_2),
+    VALUE(SPV_ENV_OPENCL_EMBEDDED_1_2),
+    VALUE(SPV_ENV_OPENCL_2_0),
+    VALUE(SPV_ENV_OPENCL_EMBEDDED_2_0),
+    VALUE(SPV_ENV_OPENCL_EMBEDDED_2_1),
+    VALUE(SPV_ENV_OPENCL_EMBEDDED_2_2),
+    VALUE(SPV_ENV_UNIVERSAL_1_3),
+    VALUE(SPV_ENV_VULKAN_1_1),
+    VALUE(SPV_ENV_WEBGPU_0),
+    VALUE(SPV_ENV_UNIVERSAL_1_4),
+    VALUE(SPV_ENV_VULKAN_1_1_SPIRV_1_4),
+    VALUE(SPV_ENV_UNIVERSAL_1_5),
+    VALUE(SPV_ENV_VULKAN_1_2),
+    VALUE(SPV_ENV_UNIVERSAL_1_6),
+    VALUE(SPV_ENV_VULKAN_1_3),
+
+    VALUE(spv_validator_limit_max_struct_members),
+    VALUE(spv_validator_limit_max_struct_depth),
+    VALUE(spv_validator_limit_max_local_variables),
+    VALUE(spv_validator_limit_max_global_variables),
+    VALUE(spv_validator_limit_max_switch_branches),
+    VALUE(spv_validator_limit_max_function_args),
+    VALUE(spv_validator_limit_max_control_flow_nesting_depth),
+    VALUE(spv_validator_limit_max_access_chain_indexes),
+    VALUE(spv_validator_limit_max_id_bound),
+
+    SIZE(spv_position_t),
+    OFFSET(spv_position_t, column),
+    OFFSET(spv_position_t, index),
+
+    SIZE(spv_diagnostic_t),
+    OFFSET(spv_diagnostic_t, error),
+    OFFSET(spv_diagnostic_t, isTextSource),
+
+    SIZE(spv_parsed_operand_t),
+    ALIGN(spv_parsed_operand_t),
+    OFFSET(spv_parsed_operand_t, num_words),
+    OFFSET(spv_parsed_operand_t, type),
+    OFFSET(spv_parsed_operand_t, number_kind),
+    OFFSET(spv_parsed_operand_t, number_bit_width),
+
+    SIZE(spv_parsed_instruction_t),
+    OFFSET(spv_parsed_instruction_t, num_words),
+    OFFSET(spv_parsed_instruction_t, opcode),
+    OFFSET(spv_parsed_instruction_t, ext_inst_type),
+    OFFSET(spv_parsed_instruction_t, result_id),
+    OFFSET(spv_parsed_instruction_t, operands),
+    OFFSET(spv_parsed_instruction_t, num_operands),
+
+    SIZE(spv_const_binary_t),
+    SIZE(spv_binary_t),
+    SIZE(spv_text_t),
+};
+
+const size_t spvrs_probe_count = sizeof(spvrs_probes) / sizeof(spvrs_probes[0]);
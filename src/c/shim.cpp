@@ -0,0 +1,192 @@
+// C interface for the parts of spirv-tools that are only exposed through its
+// C++ API, which are message consumers and the optimizer.
+
+#include <cstring>
+#include <new>
+#include <string>
+#include <vector>
+
+#include "spirv-tools/libspirv.hpp"
+#include "spirv-tools/optimizer.hpp"
+
+extern "C" {
+
+// A `spvtools::Context`, which owns its `spv_context`. The C interface has no
+// way to install a consumer on a plain `spv_context`.
+typedef struct spvrs_context_t spvrs_context_t;
+
+// A `spvtools::Optimizer`.
+typedef struct spvrs_optimizer_t spvrs_optimizer_t;
+
+typedef void (*spvrs_message_consumer)(spv_message_level_t level,
+                                       const char* source,
+                                       const spv_position_t* position,
+                                       const char* message,
+                                       void* user_data);
+
+}  // extern "C"
+
+namespace {
+
+// Forwards messages to the C callback. An empty consumer is replaced with one
+// that ignores all messages, as the optimizer expects a callable consumer.
+spvtools::MessageConsumer WrapConsumer(spvrs_message_consumer consumer,
+                                       void* user_data) {
+  if (consumer == nullptr) {
+    return [](spv_message_level_t, const char*, const spv_position_t&,
+              const char*) {};
+  }
+
+  return [consumer, user_data](spv_message_level_t level, const char* source,
+                               const spv_position_t& position,
+                               const char* message) {
+    consumer(level, source, &position, message, user_data);
+  };
+}
+
+spvtools::Optimizer* AsOptimizer(spvrs_optimizer_t* optimizer) {
+  return reinterpret_cast<spvtools::Optimizer*>(optimizer);
+}
+
+const spvtools::Optimizer* AsOptimizer(const spvrs_optimizer_t* optimizer) {
+  return reinterpret_cast<const spvtools::Optimizer*>(optimizer);
+}
+
+// The WebGPU recipes were removed from spirv-tools along with WebGPU support,
+// so they are only registered if the optimizer being built against has them.
+template <typename T>
+auto RegisterVulkanToWebGPU(T* optimizer, int)
+    -> decltype(optimizer->RegisterVulkanToWebGPUPasses(), bool()) {
+  optimizer->RegisterVulkanToWebGPUPasses();
+  return true;
+}
+
+template <typename T>
+bool RegisterVulkanToWebGPU(T*, long) {
+  return false;
+}
+
+template <typename T>
+auto RegisterWebGPUToVulkan(T* optimizer, int)
+    -> decltype(optimizer->RegisterWebGPUToVulkanPasses(), bool()) {
+  optimizer->RegisterWebGPUToVulkanPasses();
+  return true;
+}
+
+template <typename T>
+bool RegisterWebGPUToVulkan(T*, long) {
+  return false;
+}
+
+}  // namespace
+
+extern "C" {
+
+spvrs_context_t* spvrsContextCreate(spv_target_env env) {
+  auto context = new (std::nothrow) spvtools::Context(env);
+  if (context == nullptr || context->CContext() == nullptr) {
+    delete context;
+    return nullptr;
+  }
+
+  return reinterpret_cast<spvrs_context_t*>(context);
+}
+
+void spvrsContextDestroy(spvrs_context_t* context) {
+  delete reinterpret_cast<spvtools::Context*>(context);
+}
+
+spv_context spvrsContextGetContext(spvrs_context_t* context) {
+  return reinterpret_cast<spvtools::Context*>(context)->CContext();
+}
+
+void spvrsContextSetMessageConsumer(spvrs_context_t* context,
+                                    spvrs_message_consumer consumer,
+                                    void* user_data) {
+  reinterpret_cast<spvtools::Context*>(context)->SetMessageConsumer(
+      WrapConsumer(consumer, user_data));
+}
+
+spvrs_optimizer_t* spvrsOptimizerCreate(spv_target_env env) {
+  return reinterpret_cast<spvrs_optimizer_t*>(
+      new (std::nothrow) spvtools::Optimizer(env));
+}
+
+void spvrsOptimizerDestroy(spvrs_optimizer_t* optimizer) {
+  delete AsOptimizer(optimizer);
+}
+
+void spvrsOptimizerSetMessageConsumer(spvrs_optimizer_t* optimizer,
+                                      spvrs_message_consumer consumer,
+                                      void* user_data) {
+  AsOptimizer(optimizer)->SetMessageConsumer(
+      WrapConsumer(consumer, user_data));
+}
+
+void spvrsOptimizerSetTargetEnv(spvrs_optimizer_t* optimizer,
+                                spv_target_env env) {
+  AsOptimizer(optimizer)->SetTargetEnv(env);
+}
+
+bool spvrsOptimizerFlagHasValidForm(const spvrs_optimizer_t* optimizer,
+                                    const char* flag) {
+  return AsOptimizer(optimizer)->FlagHasValidForm(flag);
+}
+
+bool spvrsOptimizerRegisterPassFromFlag(spvrs_optimizer_t* optimizer,
+                                        const char* flag) {
+  return AsOptimizer(optimizer)->RegisterPassFromFlag(flag);
+}
+
+void spvrsOptimizerRegisterPerformancePasses(spvrs_optimizer_t* optimizer) {
+  AsOptimizer(optimizer)->RegisterPerformancePasses();
+}
+
+void spvrsOptimizerRegisterSizePasses(spvrs_optimizer_t* optimizer) {
+  AsOptimizer(optimizer)->RegisterSizePasses();
+}
+
+void spvrsOptimizerRegisterLegalizationPasses(spvrs_optimizer_t* optimizer) {
+  AsOptimizer(optimizer)->RegisterLegalizationPasses();
+}
+
+bool spvrsOptimizerRegisterVulkanToWebGPUPasses(spvrs_optimizer_t* optimizer) {
+  return RegisterVulkanToWebGPU(AsOptimizer(optimizer), 0);
+}
+
+bool spvrsOptimizerRegisterWebGPUToVulkanPasses(spvrs_optimizer_t* optimizer) {
+  return RegisterWebGPUToVulkan(AsOptimizer(optimizer), 0);
+}
+
+// The optimized binary is allocated the way `spvBinaryDestroy` frees it.
+bool spvrsOptimizerRun(const spvrs_optimizer_t* optimizer,
+                       const uint32_t* binary, size_t binary_size,
+                       spv_binary* optimized_binary,
+                       spv_optimizer_options options) {
+  std::vector<uint32_t> optimized;
+  bool succeeded =
+      options == nullptr
+          ? AsOptimizer(optimizer)->Run(binary, binary_size, &optimized)
+          : AsOptimizer(optimizer)->Run(binary, binary_size, &optimized,
+                                        options);
+  if (!succeeded) {
+    return false;
+  }
+
+  auto code = new (std::nothrow) uint32_t[optimized.size()];
+  auto result = new (std::nothrow) spv_binary_t;
+  if (code == nullptr || result == nullptr) {
+    delete[] code;
+    delete result;
+    return false;
+  }
+
+  std::memcpy(code, optimized.data(), optimized.size() * sizeof(uint32_t));
+  result->code = code;
+  result->wordCount = optimized.size();
+
+  *optimized_binary = result;
+  return true;
+}
+
+}  // extern "C"
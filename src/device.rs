@@ -0,0 +1,76 @@
+use crate::TargetEnv;
+
+/// The features and limits of a Vulkan device that affect which shaders it
+/// accepts, as reported by `vkGetPhysicalDeviceFeatures2` and
+/// `vkGetPhysicalDeviceProperties`.
+///
+/// The default profile is a Vulkan 1.0 device with none of the optional features
+/// and the minimum limits the specification requires.
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(default))]
+pub struct DeviceProfile {
+    /// The Vulkan version the device supports as `(major, minor)`
+    pub api_version: (u32, u32),
+    /// `VK_KHR_relaxed_block_layout`, which is core in Vulkan 1.1
+    pub relaxed_block_layout: bool,
+    /// `scalarBlockLayout`, from Vulkan 1.2 or `VK_EXT_scalar_block_layout`
+    pub scalar_block_layout: bool,
+    /// `uniformBufferStandardLayout`, from Vulkan 1.2 or
+    /// `VK_KHR_uniform_buffer_standard_layout`
+    pub uniform_buffer_standard_layout: bool,
+    /// `workgroupMemoryExplicitLayoutScalarBlockLayout`, from
+    /// `VK_KHR_workgroup_memory_explicit_layout`
+    pub workgroup_memory_explicit_layout_scalar_block_layout: bool,
+    /// `maintenance4`, which is core in Vulkan 1.3 and allows the `LocalSizeId`
+    /// execution mode
+    pub maintenance4: bool,
+    /// `maxPushConstantsSize` in bytes. Not checked during validation, as
+    /// spirv-tools has no limit for the size of push constant blocks.
+    pub max_push_constants_size: u32,
+    /// `maxComputeWorkGroupSize` in each dimension. Not checked during
+    /// validation, as spirv-tools has no limit for the `LocalSize` of a shader.
+    pub max_compute_work_group_size: [u32; 3],
+    /// `maxComputeWorkGroupInvocations`. Not checked during validation, as
+    /// spirv-tools has no limit for the `LocalSize` of a shader.
+    pub max_compute_work_group_invocations: u32
+}
+
+impl DeviceProfile {
+    /// Whether the device supports at least the given Vulkan version
+    pub fn supports_version(&self, major: u32, minor: u32) -> bool {
+        self.api_version >= (major, minor)
+    }
+
+    /// The newest Vulkan target environment the device supports
+    pub fn target_env(&self) -> TargetEnv {
+        if self.supports_version(1, 3) {
+            TargetEnv::Vulkan1_3
+        }
+        else if self.supports_version(1, 2) {
+            TargetEnv::Vulkan1_2
+        }
+        else if self.supports_version(1, 1) {
+            TargetEnv::Vulkan1_1
+        }
+        else {
+            TargetEnv::Vulkan1_0
+        }
+    }
+}
+
+impl Default for DeviceProfile {
+    fn default() -> Self {
+        Self {
+            api_version: (1, 0),
+            relaxed_block_layout: false,
+            scalar_block_layout: false,
+            uniform_buffer_standard_layout: false,
+            workgroup_memory_explicit_layout_scalar_block_layout: false,
+            maintenance4: false,
+            max_push_constants_size: 128,
+            max_compute_work_group_size: [128, 128, 64],
+            max_compute_work_group_invocations: 128
+        }
+    }
+}
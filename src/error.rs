@@ -1,12 +1,19 @@
+use std::collections::BTreeMap;
+use std::error::Error;
 use std::ffi::CStr;
 use std::fmt::{self, Display};
+use std::io;
+use std::sync::Arc;
 
+use crate::message::{Diagnostic, Message, Position};
 use crate::raw::*;
 
 /// Diagnostic info provided by spirv-tools 
-#[derive(Clone, Debug)]
+#[derive(Clone, Debug, PartialEq, Eq)]
 pub struct DiagnosticInfo {
-    diagnostic: String
+    position: Position,
+    is_text_source: bool,
+    message: String
 }
 
 impl DiagnosticInfo {
@@ -19,55 +26,75 @@ impl DiagnosticInfo {
             return None;
         }
 
-        let mut diag_str = String::with_capacity(64);
-        diag_str.push_str("Error: ");
+        let message = if (*diag).error.is_null() {
+            String::new()
+        }
+        else {
+            CStr::from_ptr((*diag).error)
+                .to_string_lossy()
+                .into_owned()
+        };
 
-        // Error is a textual error
-        if (*diag).is_text_source {
-            let line   = (*diag).position.line;
-            let column = (*diag).position.column;
-            let error  = CStr::from_ptr((*diag).error)
-                .to_str()
-                .unwrap_or("Invalid error string");
+        Some(Self {
+            position: Position::from_raw(&(*diag).position),
+            is_text_source: (*diag).isTextSource,
+            message
+        })
+    }
 
-            let out_str = format!("{}: {}: {}", line, column, error);
+    /// The zero based line in the source text the diagnostic refers to. Only
+    /// meaningful for text sources.
+    pub fn line(&self) -> usize {
+        self.position.line
+    }
 
-            diag_str.push_str(&out_str);
-        }
-        // Error is a binary error
-        else {
-            let index = (*diag).position.index;
-            let error = CStr::from_ptr((*diag).error)
-                .to_str()
-                .unwrap_or("Invalid error string");
+    /// The zero based column in the source text the diagnostic refers to. Only
+    /// meaningful for text sources.
+    pub fn column(&self) -> usize {
+        self.position.column
+    }
 
-            let out_str = format!("{}: {}", index, error);
+    /// The index of the word in the binary the diagnostic refers to. Only
+    /// meaningful for binary sources.
+    pub fn index(&self) -> usize {
+        self.position.index
+    }
 
-            diag_str.push_str(&out_str);
-        }
+    /// The full position the diagnostic refers to
+    pub fn position(&self) -> Position {
+        self.position
+    }
 
-        Some(Self { diagnostic: diag_str })
+    /// Whether the diagnostic refers to a source text rather than a binary
+    pub fn is_text_source(&self) -> bool {
+        self.is_text_source
     }
 
-    /// Convert the diagnostic info into a string
-    pub fn to_string(self) -> String {
-        self.diagnostic
+    /// The error message reported by spirv-tools
+    pub fn message(&self) -> &str {
+        &self.message
     }
 }
 
 impl Display for DiagnosticInfo {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
-        write!(f, "{}", self.diagnostic)
+        if self.is_text_source {
+            write!(f, "Error: {}: {}: {}", self.position.line, self.position.column, self.message)
+        }
+        else {
+            write!(f, "Error: {}: {}", self.position.index, self.message)
+        }
     }
 }
 
 /// An error raised during assembly
 #[derive(Clone, Debug)]
-pub enum AssembleError<'src> {
-    /// An invalid source string was provided
+pub enum AssembleError {
+    /// The source string contains a nul byte, so it can't be passed to spirv
+    /// tools
     /// 
-    /// (under)
-    InvalidSourceString(&'src str),
+    /// (byte offset of the nul)
+    InvalidSourceString(usize),
 
     /// An error that originated from spirv tools
     SpirvTools(SpvError, Option<DiagnosticInfo>)
@@ -76,6 +103,9 @@ pub enum AssembleError<'src> {
 /// An error raised during disassembly
 #[derive(Clone, Debug)]
 pub enum DisassembleError {
+    /// The binary could not be read
+    Binary(BinaryError),
+
     /// An error that originated from spirv tools
     SpirvTools(SpvError, Option<DiagnosticInfo>)
 }
@@ -83,10 +113,52 @@ pub enum DisassembleError {
 /// An error raised during validation
 #[derive(Clone, Debug)]
 pub enum ValidateError {
+    /// The binary could not be read
+    Binary(BinaryError),
+
+    /// An error that originated from spirv tools
+    SpirvTools(SpvError, Option<DiagnosticInfo>),
+
+    /// Validation failed with every error and warning the validator reported,
+    /// along with the category validation failed with. `counts` gives the number
+    /// of diagnostics per category.
+    Failed {
+        err: SpvError,
+        diagnostics: Vec<Diagnostic>
+    }
+}
+
+/// An error raised while parsing a binary
+#[derive(Clone, Debug)]
+pub enum ParseError {
     /// An error that originated from spirv tools
     SpirvTools(SpvError, Option<DiagnosticInfo>)
 }
 
+/// An error raised when a target environment name is not recognized
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum ParseTargetEnvError {
+    /// The name is not known to spirv-tools, or to this crate
+    ///
+    /// (name that failed to parse)
+    Unknown(String)
+}
+
+/// An error raised while reading a module header
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum HeaderError {
+    /// The binary is too short to contain a header
+    ///
+    /// (length of the binary in words)
+    Truncated(usize),
+
+    /// The binary does not start with the SPIR-V magic number in either byte
+    /// order
+    ///
+    /// (first word of the binary)
+    BadMagic(u32)
+}
+
 /// An error raised during optimization
 #[derive(Clone, Debug)]
 pub enum OptimizerError {
@@ -95,12 +167,229 @@ pub enum OptimizerError {
     /// (provided flag)
     InvalidFlag(String),
 
-    /// Optimization failed for some reason
-    OptimizationFailed()
+    /// Optimization failed. The messages reported by the optimizer explain why,
+    /// such as the validator rejecting the input or a pass running out of ids.
+    Failed {
+        messages: Vec<Message>
+    },
+
+    /// The binary could not be read
+    Binary(BinaryError)
 }
 
-/// An error generated by spirv-tools
+/// An error raised while reading a binary from bytes or a file
 #[derive(Clone, Debug)]
+pub enum BinaryError {
+    /// The length of the binary is not a multiple of the word size
+    ///
+    /// (length in bytes)
+    InvalidLength(usize),
+
+    /// The binary does not start with a valid header
+    Header(HeaderError),
+
+    /// The file could not be read
+    Io(Arc<io::Error>)
+}
+
+impl Error for DiagnosticInfo {}
+
+impl Display for AssembleError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            AssembleError::InvalidSourceString(offset)  => write!(f, "Assembly failed: source contains a nul byte at offset {}", offset),
+            AssembleError::SpirvTools(err, diag)        => fmt_spirv_tools(f, "Assembly", err, diag)
+        }
+    }
+}
+
+impl Error for AssembleError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match self {
+            AssembleError::InvalidSourceString(_)   => None,
+            AssembleError::SpirvTools(err, diag)    => Some(spirv_tools_source(err, diag))
+        }
+    }
+}
+
+impl Display for DisassembleError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            DisassembleError::Binary(err)           => write!(f, "Disassembly failed: {}", err),
+            DisassembleError::SpirvTools(err, diag) => fmt_spirv_tools(f, "Disassembly", err, diag)
+        }
+    }
+}
+
+impl Error for DisassembleError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match self {
+            DisassembleError::Binary(err)           => Some(err),
+            DisassembleError::SpirvTools(err, diag) => Some(spirv_tools_source(err, diag))
+        }
+    }
+}
+
+impl ValidateError {
+    /// The number of errors and warnings per category. Only `Failed` has more
+    /// than the one error that was returned, read errors have no category.
+    pub fn counts(&self) -> BTreeMap<SpvError, usize> {
+        let mut counts = BTreeMap::new();
+
+        match self {
+            ValidateError::Binary(_)                    => (),
+            ValidateError::SpirvTools(err, _)           => { counts.insert(err.clone(), 1); },
+            ValidateError::Failed { diagnostics, .. }   => {
+                for category in diagnostics.iter().filter_map(Diagnostic::category) {
+                    *counts.entry(category.clone()).or_insert(0) += 1;
+                }
+            }
+        }
+
+        counts
+    }
+}
+
+impl Display for ValidateError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            ValidateError::Binary(err)           => write!(f, "Validation failed: {}", err),
+            ValidateError::SpirvTools(err, diag) => fmt_spirv_tools(f, "Validation", err, diag),
+            ValidateError::Failed { err, diagnostics } => {
+                let mut errors = diagnostics.iter().filter(|x| x.severity().is_error());
+
+                match errors.next() {
+                    Some(diag) => {
+                        let count = errors.count() + 1;
+                        let plural = if count == 1 { "" } else { "s" };
+
+                        write!(f, "Validation failed ({}) with {} error{}: {}", err, count, plural, diag.message())
+                    },
+                    None       => write!(f, "Validation failed: {}", err)
+                }
+            }
+        }
+    }
+}
+
+impl Error for ValidateError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match self {
+            ValidateError::Binary(err)           => Some(err),
+            ValidateError::SpirvTools(err, diag) => Some(spirv_tools_source(err, diag)),
+            ValidateError::Failed { err, .. }    => Some(err)
+        }
+    }
+}
+
+impl Display for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            ParseError::SpirvTools(err, diag) => fmt_spirv_tools(f, "Parsing", err, diag)
+        }
+    }
+}
+
+impl Error for ParseError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match self {
+            ParseError::SpirvTools(err, diag) => Some(spirv_tools_source(err, diag))
+        }
+    }
+}
+
+impl Display for HeaderError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            HeaderError::Truncated(len) => write!(f, "Binary of {} words is too short for a SPIR-V header", len),
+            HeaderError::BadMagic(word) => write!(f, "Binary starts with {:#010x} instead of the SPIR-V magic number", word)
+        }
+    }
+}
+
+impl Error for HeaderError {}
+
+impl Display for ParseTargetEnvError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            ParseTargetEnvError::Unknown(name) => write!(f, "Unknown target environment '{}'", name)
+        }
+    }
+}
+
+impl Error for ParseTargetEnvError {}
+
+impl Display for OptimizerError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            OptimizerError::InvalidFlag(flag)       => write!(f, "Invalid optimizer flag '{}'", flag),
+            OptimizerError::Failed { messages }     => {
+                // The first error is the most likely cause, later ones tend to follow from it
+                match messages.iter().find(|x| x.level().is_error()).or_else(|| messages.first()) {
+                    Some(message) => write!(f, "Optimization failed: {}", message.message()),
+                    None          => write!(f, "Optimization failed")
+                }
+            },
+            OptimizerError::Binary(err)             => write!(f, "Optimization failed: {}", err)
+        }
+    }
+}
+
+impl Error for OptimizerError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match self {
+            OptimizerError::Binary(err) => Some(err),
+            _                           => None
+        }
+    }
+}
+
+impl Display for BinaryError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            BinaryError::InvalidLength(len) => write!(f, "Binary length of {} bytes is not a multiple of 4", len),
+            BinaryError::Header(err)        => write!(f, "{}", err),
+            BinaryError::Io(err)            => write!(f, "Failed to read binary: {}", err)
+        }
+    }
+}
+
+impl Error for BinaryError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match self {
+            BinaryError::InvalidLength(_)   => None,
+            BinaryError::Header(err)        => Some(err),
+            BinaryError::Io(err)            => Some(&**err)
+        }
+    }
+}
+
+impl From<HeaderError> for BinaryError {
+    fn from(err: HeaderError) -> Self {
+        BinaryError::Header(err)
+    }
+}
+
+/// Format an error that originated from spirv tools, including the diagnostic
+/// if there is one
+fn fmt_spirv_tools(f: &mut fmt::Formatter, operation: &str, err: &SpvError, diag: &Option<DiagnosticInfo>) -> fmt::Result {
+    match diag {
+        Some(diag)  => write!(f, "{} failed: {}", operation, diag),
+        None        => write!(f, "{} failed: {}", operation, err)
+    }
+}
+
+/// The source of an error that originated from spirv tools, which is the
+/// diagnostic describing it if there is one
+fn spirv_tools_source<'a>(err: &'a SpvError, diag: &'a Option<DiagnosticInfo>) -> &'a (dyn Error + 'static) {
+    match diag {
+        Some(diag)  => diag,
+        None        => err
+    }
+}
+
+/// An error generated by spirv-tools
+#[derive(Clone, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
 pub enum SpvError {
     Unsupported,
     EndOfStream,
@@ -170,4 +459,36 @@ impl SpvError {
             _                                       => panic!("Not an error!")
         }
     }
-}
\ No newline at end of file
+}
+
+impl Display for SpvError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let description = match self {
+            SpvError::Unsupported           => "the operation is not supported",
+            SpvError::EndOfStream           => "unexpected end of stream",
+            SpvError::Warning               => "the operation completed with warnings",
+            SpvError::FailedMatch           => "failed to match",
+            SpvError::RequestedTermination  => "the operation was terminated early on request",
+            SpvError::Internal              => "internal spirv-tools error",
+            SpvError::OutOfMemory           => "out of memory",
+            SpvError::InvalidPointer        => "invalid pointer",
+            SpvError::InvalidBinary         => "invalid SPIR-V binary",
+            SpvError::InvalidText           => "invalid SPIR-V assembly text",
+            SpvError::InvalidTable          => "invalid opcode or operand table",
+            SpvError::InvalidValue          => "invalid value",
+            SpvError::InvalidDiagnostic     => "invalid diagnostic",
+            SpvError::InvalidLookup         => "lookup of a name or value failed",
+            SpvError::InvalidId             => "invalid id",
+            SpvError::InvalidCfg            => "invalid control flow graph",
+            SpvError::InvalidLayout         => "invalid module layout",
+            SpvError::InvalidCapability     => "missing or invalid capability",
+            SpvError::InvalidData           => "data rules validation failed",
+            SpvError::MissingExtension      => "missing extension",
+            SpvError::WrongVersion          => "wrong SPIR-V version"
+        };
+
+        write!(f, "{}", description)
+    }
+}
+
+impl Error for SpvError {}
\ No newline at end of file
@@ -0,0 +1,139 @@
+use std::fmt::{self, Display};
+
+use crate::error::HeaderError;
+use crate::parse::Endianness;
+
+/// The magic number every SPIR-V module starts with
+pub const MAGIC_NUMBER: u32 = 0x0723_0203;
+
+/// Registered generators, indexed by their id. Taken from the registry in
+/// `spir-v.xml` of SPIRV-Headers.
+const GENERATORS: &[(&str, Option<&str>)] = &[
+    ("Khronos",                 None),
+    ("LunarG",                  None),
+    ("Valve",                   None),
+    ("Codeplay",                None),
+    ("NVIDIA",                  None),
+    ("ARM",                     None),
+    ("Khronos",                 Some("LLVM/SPIR-V Translator")),
+    ("Khronos",                 Some("SPIR-V Tools Assembler")),
+    ("Khronos",                 Some("Glslang Reference Front End")),
+    ("Qualcomm",                None),
+    ("AMD",                     None),
+    ("Intel",                   None),
+    ("Imagination",             None),
+    ("Google",                  Some("Shaderc over Glslang")),
+    ("Google",                  Some("spiregg")),
+    ("Google",                  Some("rspirv")),
+    ("X-LEGEND",                Some("Mesa-IR/SPIR-V Translator")),
+    ("Khronos",                 Some("SPIR-V Tools Linker")),
+    ("Wine",                    Some("VKD3D Shader Compiler")),
+    ("Tellusim",                Some("Clay Shader Compiler")),
+    ("W3C WebGPU Group",        Some("WHLSL Shader Translator")),
+    ("Google",                  Some("Clspv")),
+    ("Google",                  Some("MLIR SPIR-V Serializer")),
+    ("Google",                  Some("Tint Compiler")),
+    ("Google",                  Some("ANGLE Shader Compiler")),
+    ("Netease Games",           Some("Messiah Shader Compiler")),
+    ("Xenia",                   Some("Xenia Emulator Microcode Translator")),
+    ("Embark Studios",          Some("Rust GPU Compiler Backend")),
+    ("gfx-rs community",        Some("Naga")),
+    ("Mikkosoft Productions",   Some("MSP Shader Compiler")),
+    ("SpvGenTwo community",     Some("SpvGenTwo SPIR-V IR Tools")),
+    ("Google",                  Some("Skia SkSL")),
+    ("TornadoVM",               Some("SPIRV Beehive Toolkit")),
+    ("DragonJoker",             Some("ShaderWriter")),
+    ("Rayan Hatout",            Some("SPIRVSmith")),
+    ("Saarland University",     Some("Shady")),
+];
+
+/// The tool that generated a module
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct Generator {
+    /// The registered id of the tool
+    pub id: u16,
+    /// The tool specific version number
+    pub version: u16
+}
+
+impl Generator {
+    /// Split a generator word into the tool id and version
+    pub fn from_word(word: u32) -> Self {
+        Self {
+            id: (word >> 16) as u16,
+            version: word as u16
+        }
+    }
+
+    /// The vendor of the tool, if the id is registered
+    pub fn vendor(&self) -> Option<&'static str> {
+        GENERATORS.get(self.id as usize)
+            .map(|x| x.0)
+    }
+
+    /// The name of the tool, if the id is registered with one
+    pub fn tool(&self) -> Option<&'static str> {
+        GENERATORS.get(self.id as usize)
+            .and_then(|x| x.1)
+    }
+}
+
+// Formats the generator the same way as the disassembler, e.g.
+// `Khronos Glslang Reference Front End; 8`
+impl Display for Generator {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match (self.vendor(), self.tool()) {
+            (Some(vendor), Some(tool))  => write!(f, "{} {}; {}", vendor, tool, self.version),
+            (Some(vendor), None)        => write!(f, "{}; {}", vendor, self.version),
+            _                           => write!(f, "Unknown({}); {}", self.id, self.version)
+        }
+    }
+}
+
+/// The header of a SPIR-V module, read without going through spirv-tools
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct ModuleHeader {
+    /// Byte order of the module in memory. Big endian modules on a little
+    /// endian machine, or the other way around, have their words swapped.
+    pub endianness: Endianness,
+    /// Major and minor version of SPIR-V the module uses
+    pub version: (u8, u8),
+    /// The tool that generated the module
+    pub generator: Generator,
+    /// All ids in the module are less than this bound
+    pub bound: u32,
+    /// Reserved for an instruction schema, currently always 0
+    pub schema: u32
+}
+
+impl ModuleHeader {
+    /// Number of words in the header
+    pub const WORDS: usize = 5;
+
+    /// Read the header at the start of a module.
+    ///
+    /// Modules with the opposite byte order to the machine are detected from the
+    /// magic number and their header words are swapped before decoding.
+    pub fn parse(binary: &[u32]) -> Result<Self, HeaderError> {
+        if binary.len() < Self::WORDS {
+            return Err(HeaderError::Truncated(binary.len()));
+        }
+
+        let (endianness, swap) = match binary[0] {
+            MAGIC_NUMBER                            => (Endianness::NATIVE, false),
+            x if x.swap_bytes() == MAGIC_NUMBER     => (Endianness::NATIVE.swapped(), true),
+            x                                       => return Err(HeaderError::BadMagic(x))
+        };
+
+        let word = |i: usize| if swap { binary[i].swap_bytes() } else { binary[i] };
+        let version = word(1);
+
+        Ok(Self {
+            endianness,
+            version: ((version >> 16) as u8, (version >> 8) as u8),
+            generator: Generator::from_word(word(2)),
+            bound: word(3),
+            schema: word(4)
+        })
+    }
+}
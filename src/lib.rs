@@ -7,22 +7,48 @@
 //! The crate root contains the safe wrapper over the bindings
 //! `raw` contains the raw bindings
 
+mod binary;
+mod buf;
+mod device;
 mod error;
+mod header;
+mod message;
 mod opt;
+mod parse;
+mod pass;
+mod render;
+mod report;
+mod version;
 
 pub mod raw;
 
+pub use binary::*;
+pub use buf::*;
+pub use device::*;
 pub use error::*;
+pub use header::*;
+pub use message::*;
 pub use opt::*;
+pub use parse::*;
+pub use pass::*;
+pub use render::*;
+pub use report::*;
+pub use version::*;
 
-use std::ffi::CString;
+use std::ffi::{CStr, CString};
+use std::fmt::{self, Display};
+use std::mem;
+use std::ops::ControlFlow;
+use std::path::Path;
 use std::ptr;
-use std::slice;
-use std::str;
+use std::str::{self, FromStr};
 
 use self::raw::*;
 
-#[derive(Clone, Copy)]
+/// The environment a module is processed for. New environments are added as
+/// spirv-tools supports them.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+#[non_exhaustive]
 pub enum TargetEnv {
     /// SPIR-V 1.0 latest revision, no other restrictions
     Universal1_0,
@@ -67,9 +93,165 @@ pub enum TargetEnv {
     Vulkan1_1,
     /// Work in progress WebGPU 1.0
     WebGPU0,
+    /// SPIR-V 1.4 latest revision, no other restrictions
+    Universal1_4,
+    /// Vulkan 1.1 with VK_KHR_spirv_1_4, i.e. SPIR-V 1.4 binary
+    Vulkan1_1Spirv1_4,
+    /// SPIR-V 1.5 latest revision, no other restrictions
+    Universal1_5,
+    /// Vulkan 1.2 latest revision
+    Vulkan1_2,
+    /// SPIR-V 1.6 latest revision, no other restrictions
+    Universal1_6,
+    /// Vulkan 1.3 latest revision
+    Vulkan1_3,
+}
+
+/// The API family a target environment belongs to
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum TargetEnvFamily {
+    /// Plain SPIR-V, no other restrictions
+    Universal,
+    Vulkan,
+    OpenCL,
+    OpenGL,
+    WebGPU
 }
 
 impl TargetEnv {
+    /// The highest SPIR-V version, as major and minor, a module for the
+    /// environment can use
+    pub fn max_spirv_version(self) -> (u8, u8) {
+        match self {
+            TargetEnv::Universal1_0          => (1, 0),
+            TargetEnv::Vulkan1_0             => (1, 0),
+            TargetEnv::Universal1_1          => (1, 1),
+            TargetEnv::OpenCL2_1             => (1, 0),
+            TargetEnv::OpenCL2_2             => (1, 2),
+            TargetEnv::OpenGL4_0             => (1, 0),
+            TargetEnv::OpenGL4_1             => (1, 0),
+            TargetEnv::OpenGL4_2             => (1, 0),
+            TargetEnv::OpenGL4_3             => (1, 0),
+            TargetEnv::OpenGl4_5             => (1, 0),
+            TargetEnv::Universal1_2          => (1, 2),
+            TargetEnv::OpenCL1_2             => (1, 0),
+            TargetEnv::OpenCLEmbedded1_2     => (1, 0),
+            TargetEnv::OpenCL2_0             => (1, 0),
+            TargetEnv::OpenCLEmbedded2_0     => (1, 0),
+            TargetEnv::OpenCLEmbedded2_1     => (1, 0),
+            TargetEnv::OpenCLEmbedded2_2     => (1, 2),
+            TargetEnv::Universal1_3          => (1, 3),
+            TargetEnv::Vulkan1_1             => (1, 3),
+            TargetEnv::WebGPU0               => (1, 3),
+            TargetEnv::Universal1_4          => (1, 4),
+            TargetEnv::Vulkan1_1Spirv1_4     => (1, 4),
+            TargetEnv::Universal1_5          => (1, 5),
+            TargetEnv::Vulkan1_2             => (1, 5),
+            TargetEnv::Universal1_6          => (1, 6),
+            TargetEnv::Vulkan1_3             => (1, 6)
+        }
+    }
+
+    /// The API family of the environment
+    pub fn family(self) -> TargetEnvFamily {
+        match self {
+            TargetEnv::Universal1_0
+            | TargetEnv::Universal1_1
+            | TargetEnv::Universal1_2
+            | TargetEnv::Universal1_3
+            | TargetEnv::Universal1_4
+            | TargetEnv::Universal1_5
+            | TargetEnv::Universal1_6        => TargetEnvFamily::Universal,
+            TargetEnv::Vulkan1_0
+            | TargetEnv::Vulkan1_1
+            | TargetEnv::Vulkan1_1Spirv1_4
+            | TargetEnv::Vulkan1_2
+            | TargetEnv::Vulkan1_3           => TargetEnvFamily::Vulkan,
+            TargetEnv::OpenCL1_2
+            | TargetEnv::OpenCLEmbedded1_2
+            | TargetEnv::OpenCL2_0
+            | TargetEnv::OpenCLEmbedded2_0
+            | TargetEnv::OpenCL2_1
+            | TargetEnv::OpenCLEmbedded2_1
+            | TargetEnv::OpenCL2_2
+            | TargetEnv::OpenCLEmbedded2_2   => TargetEnvFamily::OpenCL,
+            TargetEnv::OpenGL4_0
+            | TargetEnv::OpenGL4_1
+            | TargetEnv::OpenGL4_2
+            | TargetEnv::OpenGL4_3
+            | TargetEnv::OpenGl4_5           => TargetEnvFamily::OpenGL,
+            TargetEnv::WebGPU0               => TargetEnvFamily::WebGPU
+        }
+    }
+
+    /// The name spirv-tools uses for the environment on the command line, such as
+    /// `vulkan1.1`. Parsing the name gives back the environment.
+    pub fn name(self) -> &'static str {
+        match self {
+            TargetEnv::Universal1_0          => "spv1.0",
+            TargetEnv::Vulkan1_0             => "vulkan1.0",
+            TargetEnv::Universal1_1          => "spv1.1",
+            TargetEnv::OpenCL2_1             => "opencl2.1",
+            TargetEnv::OpenCL2_2             => "opencl2.2",
+            TargetEnv::OpenGL4_0             => "opengl4.0",
+            TargetEnv::OpenGL4_1             => "opengl4.1",
+            TargetEnv::OpenGL4_2             => "opengl4.2",
+            TargetEnv::OpenGL4_3             => "opengl4.3",
+            TargetEnv::OpenGl4_5             => "opengl4.5",
+            TargetEnv::Universal1_2          => "spv1.2",
+            TargetEnv::OpenCL1_2             => "opencl1.2",
+            TargetEnv::OpenCLEmbedded1_2     => "opencl1.2embedded",
+            TargetEnv::OpenCL2_0             => "opencl2.0",
+            TargetEnv::OpenCLEmbedded2_0     => "opencl2.0embedded",
+            TargetEnv::OpenCLEmbedded2_1     => "opencl2.1embedded",
+            TargetEnv::OpenCLEmbedded2_2     => "opencl2.2embedded",
+            TargetEnv::Universal1_3          => "spv1.3",
+            TargetEnv::Vulkan1_1             => "vulkan1.1",
+            TargetEnv::WebGPU0               => "webgpu0",
+            TargetEnv::Universal1_4          => "spv1.4",
+            TargetEnv::Vulkan1_1Spirv1_4     => "vulkan1.1spv1.4",
+            TargetEnv::Universal1_5          => "spv1.5",
+            TargetEnv::Vulkan1_2             => "vulkan1.2",
+            TargetEnv::Universal1_6          => "spv1.6",
+            TargetEnv::Vulkan1_3             => "vulkan1.3"
+        }
+    }
+
+    /// Convert the C version of `TargetEnv` into the rust version, if it is known
+    fn from_raw(env: spv_target_env) -> Option<Self> {
+        let env = match env {
+            spv_target_env::UNIVERSAL_1_0        => TargetEnv::Universal1_0,
+            spv_target_env::VULKAN_1_0           => TargetEnv::Vulkan1_0,
+            spv_target_env::UNIVERSAL_1_1        => TargetEnv::Universal1_1,
+            spv_target_env::OPENCL_2_1           => TargetEnv::OpenCL2_1,
+            spv_target_env::OPENCL_2_2           => TargetEnv::OpenCL2_2,
+            spv_target_env::OPENGL_4_0           => TargetEnv::OpenGL4_0,
+            spv_target_env::OPENGL_4_1           => TargetEnv::OpenGL4_1,
+            spv_target_env::OPENGL_4_2           => TargetEnv::OpenGL4_2,
+            spv_target_env::OPENGL_4_3           => TargetEnv::OpenGL4_3,
+            spv_target_env::OPENGL_4_5           => TargetEnv::OpenGl4_5,
+            spv_target_env::UNIVERSAL_1_2        => TargetEnv::Universal1_2,
+            spv_target_env::OPENCL_1_2           => TargetEnv::OpenCL1_2,
+            spv_target_env::OPENCL_EMBEDDED_1_2  => TargetEnv::OpenCLEmbedded1_2,
+            spv_target_env::OPENCL_2_0           => TargetEnv::OpenCL2_0,
+            spv_target_env::OPENCL_EMBEDDED_2_0  => TargetEnv::OpenCLEmbedded2_0,
+            spv_target_env::OPENCL_EMBEDDED_2_1  => TargetEnv::OpenCLEmbedded2_1,
+            spv_target_env::OPENCL_EMBEDDED_2_2  => TargetEnv::OpenCLEmbedded2_2,
+            spv_target_env::UNIVERSAL_1_3        => TargetEnv::Universal1_3,
+            spv_target_env::VULKAN_1_1           => TargetEnv::Vulkan1_1,
+            spv_target_env::WEBGPU_0             => TargetEnv::WebGPU0,
+            spv_target_env::UNIVERSAL_1_4        => TargetEnv::Universal1_4,
+            spv_target_env::VULKAN_1_1_SPIRV_1_4 => TargetEnv::Vulkan1_1Spirv1_4,
+            spv_target_env::UNIVERSAL_1_5        => TargetEnv::Universal1_5,
+            spv_target_env::VULKAN_1_2           => TargetEnv::Vulkan1_2,
+            spv_target_env::UNIVERSAL_1_6        => TargetEnv::Universal1_6,
+            spv_target_env::VULKAN_1_3           => TargetEnv::Vulkan1_3,
+            _                                    => return None
+        };
+
+        Some(env)
+    }
+
     /// Convert the rust version of `TargetEnv` into the C version
     fn to_raw(self) -> spv_target_env {
         match self {
@@ -92,11 +274,93 @@ impl TargetEnv {
             TargetEnv::OpenCLEmbedded2_2     => spv_target_env::OPENCL_EMBEDDED_2_2,
             TargetEnv::Universal1_3          => spv_target_env::UNIVERSAL_1_3,
             TargetEnv::Vulkan1_1             => spv_target_env::VULKAN_1_1,
-            TargetEnv::WebGPU0               => spv_target_env::WEBGPU_0
+            TargetEnv::WebGPU0               => spv_target_env::WEBGPU_0,
+            TargetEnv::Universal1_4          => spv_target_env::UNIVERSAL_1_4,
+            TargetEnv::Vulkan1_1Spirv1_4     => spv_target_env::VULKAN_1_1_SPIRV_1_4,
+            TargetEnv::Universal1_5          => spv_target_env::UNIVERSAL_1_5,
+            TargetEnv::Vulkan1_2             => spv_target_env::VULKAN_1_2,
+            TargetEnv::Universal1_6          => spv_target_env::UNIVERSAL_1_6,
+            TargetEnv::Vulkan1_3             => spv_target_env::VULKAN_1_3
         }
     }
 }
 
+// Parses the names spirv-tools accepts on the command line, such as `vulkan1.1`
+// or `spv1.3`
+impl FromStr for TargetEnv {
+    type Err = ParseTargetEnvError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let unknown = || ParseTargetEnvError::Unknown(s.to_owned());
+
+        // spirv-tools no longer parses the deprecated WebGPU environment
+        if s == TargetEnv::WebGPU0.name() {
+            return Ok(TargetEnv::WebGPU0);
+        }
+
+        let name = CString::new(s)
+            .map_err(|_| unknown())?;
+
+        let mut env = spv_target_env::UNIVERSAL_1_0;
+        let parsed = unsafe { spvParseTargetEnv(name.as_ptr(), &mut env) };
+
+        // spirv-tools only matches the start of the name, so `vulkan1.1garbage`
+        // parses as `vulkan1.1` unless the whole name is checked
+        TargetEnv::from_raw(env)
+            .filter(|x| parsed && x.name() == s)
+            .ok_or_else(unknown)
+    }
+}
+
+// Uses the spirv-tools description, such as `SPIR-V 1.3 (under Vulkan 1.1 semantics)`,
+// falling back to the name for environments without one
+impl Display for TargetEnv {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        // spirv-tools asserts on deprecated environments instead of describing them
+        if *self == TargetEnv::WebGPU0 {
+            return f.write_str(self.name());
+        }
+
+        let description = unsafe { spvTargetEnvDescription(self.to_raw()) };
+        if description.is_null() {
+            return f.write_str(self.name());
+        }
+
+        // Builds without assertions describe deprecated environments as empty
+        let description = unsafe { CStr::from_ptr(description) }.to_string_lossy();
+        if description.is_empty() {
+            f.write_str(self.name())
+        }
+        else {
+            f.write_str(&description)
+        }
+    }
+}
+
+/// Options for assembling a spirv binary
+#[derive(Clone, Copy)]
+pub struct AssembleOptions {
+    raw: u32
+}
+
+impl AssembleOptions {
+    pub fn none() -> Self {
+        Self { raw: 0 }
+    }
+
+    /// Numeric IDs in the binary will have the same values as in the source.
+    /// Non-numeric IDs are allocated by filling in the gaps, starting with 1
+    /// and going up.
+    pub fn preserve_numeric_ids(self) -> Self {
+        Self { raw: self.raw | (1 << 1) }
+    }
+
+    #[inline]
+    fn into_raw(self) -> u32 {
+        self.raw
+    }
+}
+
 /// Options for dissassembling a spirv binary
 #[derive(Clone, Copy)]
 pub struct DisassembleOptions {
@@ -138,6 +402,8 @@ impl DisassembleOptions {
     }
 }
 
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum ValidatorLimit {
     MaxStructMembers(u32),
     MaxStructDept(u32),
@@ -150,22 +416,90 @@ pub enum ValidatorLimit {
     MaxIdBound(u32)
 }
 
+/// The values configured on a set of validator options. Options that were never
+/// set are `None` and use the spirv-tools defaults.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(default))]
+pub struct ValidatorSettings {
+    /// The universal limits that were set, at most one of each kind
+    pub limits: Vec<ValidatorLimit>,
+    pub relax_store_struct: Option<bool>,
+    pub relax_logical_pointer: Option<bool>,
+    pub before_hlsl_legalization: Option<bool>,
+    pub relax_block_layout: Option<bool>,
+    pub uniform_buffer_standard_layout: Option<bool>,
+    pub scalar_block_layout: Option<bool>,
+    pub workgroup_scalar_block_layout: Option<bool>,
+    pub skip_block_layout: Option<bool>,
+    pub allow_local_size_id: Option<bool>,
+    pub friendly_names: Option<bool>
+}
+
+impl ValidatorLimit {
+    /// The value of the limit
+    pub fn value(&self) -> u32 {
+        match *self {
+            ValidatorLimit::MaxStructMembers(x)             => x,
+            ValidatorLimit::MaxStructDept(x)                => x,
+            ValidatorLimit::MaxLocalVariables(x)            => x,
+            ValidatorLimit::MaxGlobalVariables(x)           => x,
+            ValidatorLimit::MaxSwitchBranches(x)            => x,
+            ValidatorLimit::MaxFunctionArgs(x)              => x,
+            ValidatorLimit::MaxControlFlowNestingDepth(x)   => x,
+            ValidatorLimit::MaxAccessChainIndexes(x)        => x,
+            ValidatorLimit::MaxIdBound(x)                   => x
+        }
+    }
+}
+
 /// A set of options for controlling validation
+///
+/// The configured values are recorded alongside the spirv-tools options, so the
+/// options can be cloned, compared and inspected with `settings`, and passed by
+/// reference to any number of validations.
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(from = "ValidatorSettings", into = "ValidatorSettings"))]
 pub struct ValidatorOptions {
-    raw: spv_validator_options
+    raw: spv_validator_options,
+    settings: ValidatorSettings
 }
 
 impl ValidatorOptions {
     /// Create a new validator options
     pub fn new() -> Self {
         Self { 
-            raw: unsafe { spvValidatorOptionsCreate() }
+            raw: unsafe { spvValidatorOptionsCreate() },
+            settings: ValidatorSettings::default()
         }
     }
 
+    /// The values that were configured on the options
+    pub fn settings(&self) -> &ValidatorSettings {
+        &self.settings
+    }
+
+    /// Create options that accept exactly what the device supports. Validate with
+    /// a context for `profile.target_env()`.
+    ///
+    /// The features of the device enable the matching block layout relaxations and
+    /// `LocalSizeId`. The push constant and compute workgroup limits of the
+    /// profile are not enforced: the validator options of spirv-tools only limit
+    /// the shape of a module, such as its number of ids or struct members, and
+    /// have nothing matching these device limits. Modules that exceed them pass
+    /// validation and fail when the pipeline is created.
+    pub fn from_device_profile(profile: &DeviceProfile) -> Self {
+        Self::new()
+            .relax_block_layout(profile.relaxed_block_layout || profile.supports_version(1, 1))
+            .scalar_block_layout(profile.scalar_block_layout)
+            .uniform_buffer_standard_layout(profile.uniform_buffer_standard_layout)
+            .workgroup_scalar_block_layout(profile.workgroup_memory_explicit_layout_scalar_block_layout)
+            .allow_local_size_id(profile.maintenance4 || profile.supports_version(1, 3))
+    }
+
     /// Records the maximum Universal Limit that is considered valid in the given
     /// Validator options object
-    pub fn limit(self, limit: ValidatorLimit) -> Self {
+    pub fn limit(mut self, limit: ValidatorLimit) -> Self {
         let (limit_type, value) = match limit {
             ValidatorLimit::MaxStructMembers(x)             => (spv_validator_limit::max_struct_members             , x),
             ValidatorLimit::MaxStructDept(x)                => (spv_validator_limit::max_struct_depth               , x),
@@ -179,6 +513,14 @@ impl ValidatorOptions {
         };
 
         unsafe { spvValidatorOptionsSetUniversalLimit(self.raw, limit_type, value) };
+
+        // A later limit of the same kind replaces the earlier one
+        let limits = &mut self.settings.limits;
+        match limits.iter_mut().find(|x| mem::discriminant(*x) == mem::discriminant(&limit)) {
+            Some(x) => *x = limit,
+            None    => limits.push(limit)
+        }
+
         self
     }
 
@@ -188,12 +530,13 @@ impl ValidatorOptions {
     /// if
     ///
     /// 1) the members of the structs are either the same type or are structs with
-    /// same layout, and
+    ///    same layout, and
     ///
     /// 2) the decorations that affect the memory layout are identical for both
-    /// types.  Other decorations are not relevant.
-    pub fn relax_store_struct(self, relax_store: bool) -> Self {
+    ///    types.  Other decorations are not relevant.
+    pub fn relax_store_struct(mut self, relax_store: bool) -> Self {
         unsafe { spvValidatorOptionsSetRelaxStoreStruct(self.raw, relax_store); }
+        self.settings.relax_store_struct = Some(relax_store);
         self
     }
 
@@ -203,8 +546,30 @@ impl ValidatorOptions {
     /// When relaxed, it will allow the following usage cases of pointers:
     /// 1) OpVariable allocating an object whose type is a pointer type
     /// 2) OpReturnValue returning a pointer value
-    pub fn relax_logical_pointer(self, relax_ptr: bool) -> Self {
+    pub fn relax_logical_pointer(mut self, relax_ptr: bool) -> Self {
         unsafe { spvValidatorOptionsSetRelaxLogicalPointer(self.raw, relax_ptr); }
+        self.settings.relax_logical_pointer = Some(relax_ptr);
+        self
+    }
+
+    /// Records whether or not the validator should relax the rules because it is
+    /// expected that the optimizations will make the code legal, such as for the
+    /// output of an HLSL front-end before legalization.
+    ///
+    /// When relaxed, it will allow the following:
+    /// 1) Relaxed logical pointers. Setting this option also sets that option.
+    /// 2) Pointers that are passed as parameters to function calls do not have to
+    ///    match the storage class of the formal parameter.
+    /// 3) Pointers that are actual parameters on function calls do not have to
+    ///    point to the same type pointed as the formal parameter. The types just
+    ///    need to logically match.
+    /// 4) GLSLstd450 Interpolate* instructions can have a load of an interpolant
+    ///    for a first argument.
+    pub fn before_hlsl_legalization(mut self, before_legalization: bool) -> Self {
+        unsafe { spvValidatorOptionsSetBeforeHlslLegalization(self.raw, before_legalization); }
+        // Setting this option also sets relax_logical_pointer
+        self.settings.before_hlsl_legalization = Some(before_legalization);
+        self.settings.relax_logical_pointer = Some(before_legalization);
         self
     }
 
@@ -215,8 +580,18 @@ impl ValidatorOptions {
     ///
     /// This is enabled by default when targeting Vulkan 1.1 or later.
     /// Relaxed layout is more permissive than the default rules in Vulkan 1.0.
-    pub fn relax_block_layout(self, relax_layout: bool) -> Self {
+    pub fn relax_block_layout(mut self, relax_layout: bool) -> Self {
         unsafe { spvValidatorOptionsSetRelaxBlockLayout(self.raw, relax_layout); }
+        self.settings.relax_block_layout = Some(relax_layout);
+        self
+    }
+
+    /// Records whether the validator should use standard block layout rules for
+    /// uniform blocks, as described by Vulkan extension
+    /// VK_KHR_uniform_buffer_standard_layout.
+    pub fn uniform_buffer_standard_layout(mut self, standard_layout: bool) -> Self {
+        unsafe { spvValidatorOptionsSetUniformBufferStandardLayout(self.raw, standard_layout); }
+        self.settings.uniform_buffer_standard_layout = Some(standard_layout);
         self
     }
 
@@ -236,19 +611,51 @@ impl ValidatorOptions {
     /// - a member Offset must be a multiple of the member's scalar alignment
     /// - ArrayStride or MatrixStride must be a multiple of the array or matrix
     ///   scalar alignment
-    pub fn scalar_block_layout(self, scalar_layout: bool) -> Self {
+    pub fn scalar_block_layout(mut self, scalar_layout: bool) -> Self {
         unsafe { spvValidatorOptionsSetScalarBlockLayout(self.raw, scalar_layout); }
+        self.settings.scalar_block_layout = Some(scalar_layout);
+        self
+    }
+
+    /// Records whether the validator should use "scalar" block layout rules for
+    /// Workgroup blocks. See Vulkan extension
+    /// VK_KHR_workgroup_memory_explicit_layout.
+    pub fn workgroup_scalar_block_layout(mut self, scalar_layout: bool) -> Self {
+        unsafe { spvValidatorOptionsSetWorkgroupScalarBlockLayout(self.raw, scalar_layout); }
+        self.settings.workgroup_scalar_block_layout = Some(scalar_layout);
         self
     }
 
     /// Records whether or not the validator should skip validating standard
     /// uniform/storage block layout.
-    pub fn skip_block_layout(self, skip_layout: bool) -> Self {
+    pub fn skip_block_layout(mut self, skip_layout: bool) -> Self {
         unsafe { spvValidatorOptionsSetSkipBlockLayout(self.raw, skip_layout); }
+        self.settings.skip_block_layout = Some(skip_layout);
+        self
+    }
+
+    /// Records whether or not the validator should allow the LocalSizeId
+    /// execution mode where the environment otherwise would not allow it.
+    pub fn allow_local_size_id(mut self, allow: bool) -> Self {
+        unsafe { spvValidatorOptionsSetAllowLocalSizeId(self.raw, allow); }
+        self.settings.allow_local_size_id = Some(allow);
+        self
+    }
+
+    /// Records whether validation error messages should refer to ids by their
+    /// friendly names, such as `%main`, rather than by number.
+    pub fn friendly_names(mut self, friendly_names: bool) -> Self {
+        unsafe { spvValidatorOptionsSetFriendlyNames(self.raw, friendly_names); }
+        self.settings.friendly_names = Some(friendly_names);
         self
     }
 }
 
+// Validator options are only modified by value and read by the functions they are
+// passed to, so they can be shared between threads
+unsafe impl Send for ValidatorOptions {}
+unsafe impl Sync for ValidatorOptions {}
+
 impl Drop for ValidatorOptions {
     fn drop(&mut self) {
         unsafe { spvValidatorOptionsDestroy(self.raw); }
@@ -261,19 +668,125 @@ impl Default for ValidatorOptions {
     }
 }
 
+impl From<ValidatorSettings> for ValidatorOptions {
+    /// Create validator options with the given values
+    fn from(settings: ValidatorSettings) -> Self {
+        let mut options = ValidatorOptions::new();
+
+        for limit in settings.limits {
+            options = options.limit(limit);
+        }
+
+        // Legalization also sets relax_logical_pointer, so it goes first to not
+        // override a recorded value of that
+        if let Some(value) = settings.before_hlsl_legalization {
+            options = options.before_hlsl_legalization(value);
+        }
+
+        if let Some(value) = settings.relax_logical_pointer {
+            options = options.relax_logical_pointer(value);
+        }
+
+        if let Some(value) = settings.relax_store_struct {
+            options = options.relax_store_struct(value);
+        }
+
+        if let Some(value) = settings.relax_block_layout {
+            options = options.relax_block_layout(value);
+        }
+
+        if let Some(value) = settings.uniform_buffer_standard_layout {
+            options = options.uniform_buffer_standard_layout(value);
+        }
+
+        if let Some(value) = settings.scalar_block_layout {
+            options = options.scalar_block_layout(value);
+        }
+
+        if let Some(value) = settings.workgroup_scalar_block_layout {
+            options = options.workgroup_scalar_block_layout(value);
+        }
+
+        if let Some(value) = settings.skip_block_layout {
+            options = options.skip_block_layout(value);
+        }
+
+        if let Some(value) = settings.allow_local_size_id {
+            options = options.allow_local_size_id(value);
+        }
+
+        if let Some(value) = settings.friendly_names {
+            options = options.friendly_names(value);
+        }
+
+        options
+    }
+}
+
+impl From<ValidatorOptions> for ValidatorSettings {
+    fn from(options: ValidatorOptions) -> Self {
+        options.settings.clone()
+    }
+}
+
+// Clones replay the recorded settings on new options
+impl Clone for ValidatorOptions {
+    fn clone(&self) -> Self {
+        Self::from(self.settings.clone())
+    }
+}
+
+impl PartialEq for ValidatorOptions {
+    fn eq(&self, other: &Self) -> bool {
+        self.settings == other.settings
+    }
+}
+
+impl Eq for ValidatorOptions {}
+
+impl fmt::Debug for ValidatorOptions {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("ValidatorOptions")
+            .field("settings", &self.settings)
+            .finish()
+    }
+}
+
 /// A context for invoking spirv-tools
+///
+/// The underlying `spv_context` is created once and reused for every operation,
+/// so a single context can be kept around and shared between threads for batch
+/// processing.
 pub struct Context {
-    env: TargetEnv,
-    include_diagnostics: bool
+    handle: spvrs_context,
+    raw: spv_context,
+    include_diagnostics: bool,
+    // A consumer is always installed so validation can collect its messages
+    consumer: MessageConsumer
 }
 
+// The spirv-tools C API never mutates a context after it has been created. The
+// diagnostics for an operation are collected through a temporary copy of it, so
+// using one context from several threads at once is sound. Message consumers
+// are only replaced through `&mut self` and serialize their own calls.
+unsafe impl Send for Context {}
+unsafe impl Sync for Context {}
+
 impl Context {
     /// Create a new context for the target environment
     pub fn new(env: TargetEnv) -> Self {
-        Self {
-            env: env,
-            include_diagnostics: false
-        }
+        let handle = unsafe { spvrsContextCreate(env.to_raw()) };
+        assert!(!handle.is_null(), "Failed to create a spirv-tools context");
+
+        let mut ctx = Self {
+            handle,
+            raw: unsafe { spvrsContextGetContext(handle) },
+            include_diagnostics: false,
+            consumer: MessageConsumer::new(|_| {})
+        };
+
+        ctx.install_consumer();
+        ctx
     }
 
     /// Include diagnostic information in any error codes
@@ -282,13 +795,42 @@ impl Context {
         self
     }
 
-    /// Assemble a spirv binary from it's textual form
-    pub fn assemble<'src>(&self, source: &'src str) -> Result<Vec<u32>, AssembleError<'src>> {
+    /// Set a consumer for the errors, warnings and info messages reported while
+    /// assembling, disassembling or validating, replacing any previous consumer.
+    ///
+    /// When diagnostics are included, the first error is returned in the
+    /// diagnostic instead and the consumer is not called. A panic in the
+    /// consumer is resumed once the operation reporting the message returns.
+    /// Messages of operations the consumer runs on this context itself are not
+    /// passed back to it.
+    pub fn set_message_consumer<F: FnMut(Message) + Send + 'static>(&mut self, consumer: F) {
+        self.consumer = MessageConsumer::new(consumer);
+        self.install_consumer();
+    }
+
+    /// Remove the message consumer, ignoring all messages from now on
+    pub fn clear_message_consumer(&mut self) {
+        self.consumer = MessageConsumer::new(|_| {});
+        self.install_consumer();
+    }
+
+    /// Point spirv-tools at the current consumer
+    fn install_consumer(&mut self) {
+        unsafe { spvrsContextSetMessageConsumer(self.handle, self.consumer.callback(), self.consumer.user_data()); }
+    }
+
+    /// Assemble a spirv binary from it's textual form using default options
+    #[inline]
+    pub fn assemble(&self, source: &str) -> Result<SpvBinaryBuf, AssembleError> {
+        self.assemble_with_options(source, AssembleOptions::none())
+    }
+
+    /// Assemble a spirv binary from it's textual form with the specified options
+    pub fn assemble_with_options(&self, source: &str, options: AssembleOptions) -> Result<SpvBinaryBuf, AssembleError> {
         unsafe {
             // Setup to call the C library
-            let context = spvContextCreate(self.env.to_raw());
             let src = CString::new(source)
-                .map_err(|_| AssembleError::InvalidSourceString(source))?;
+                .map_err(|e| AssembleError::InvalidSourceString(e.nul_position()))?;
             
             // Assemble the source code
             let (err_code, bin, diag) = {
@@ -299,19 +841,21 @@ impl Context {
                 let mut out_diag = ptr::null_mut();
     
                 let result = if self.include_diagnostics {
-                    spvTextToBinary(
-                        context, 
+                    spvTextToBinaryWithOptions(
+                        self.raw, 
                         str_ptr,
                         str_len,
+                        options.into_raw(),
                         &mut out_bin as *mut spv_binary,
                         &mut out_diag as *mut spv_diagnostic
                     )
                 }
                 else {
-                    spvTextToBinary(
-                        context, 
+                    spvTextToBinaryWithOptions(
+                        self.raw, 
                         str_ptr,
                         str_len,
+                        options.into_raw(),
                         &mut out_bin as *mut spv_binary,
                         ptr::null_mut()
                     )
@@ -320,22 +864,16 @@ impl Context {
                 (result, out_bin, out_diag)
             };
     
-            // Copy over the resulting code from C memory to Rust memory
+            // Hand the resulting code over to the buffer, which frees it on drop
             let result = match err_code {
-                spv_result_t::SUCCESS => {
-                    let slice = slice::from_raw_parts(
-                        (*bin).code, 
-                        (*bin).word_count as usize
-                    );
-    
-                    let mut binary = Vec::with_capacity(slice.len());
-                    binary.set_len(slice.len());
-                    binary.copy_from_slice(slice);
-    
-                    Ok(binary)
-                },
+                spv_result_t::SUCCESS => Ok(SpvBinaryBuf::from_raw(bin)),
                 _                     => {
                     let (err, diag) = SpvError::from_raw(err_code, diag);
+
+                    if !bin.is_null() {
+                        spvBinaryDestroy(bin);
+                    }
+
                     Err(AssembleError::SpirvTools(err, diag))
                 }
             };
@@ -345,11 +883,7 @@ impl Context {
                 spvDiagnosticDestroy(diag);
             }
     
-            if !bin.is_null() {
-                spvBinaryDestroy(bin);
-            }
-    
-            spvContextDestroy(context);
+            MessageConsumer::resume_panic();
     
             result
         }
@@ -357,23 +891,21 @@ impl Context {
 
     /// Disassemble a spirv binary into it's textual form using default options
     #[inline]
-    pub fn disassemble(&self, binary: &[u32]) -> Result<String, DisassembleError> {
+    pub fn disassemble(&self, binary: &[u32]) -> Result<SpvTextBuf, DisassembleError> {
         self.disassemble_with_options(binary, DisassembleOptions::none())
     }
 
     /// Disassemble a spirv binary into it's textual form with the specified options
-    pub fn disassemble_with_options(&self, binary: &[u32], options: DisassembleOptions) -> Result<String, DisassembleError> {
+    pub fn disassemble_with_options(&self, binary: &[u32], options: DisassembleOptions) -> Result<SpvTextBuf, DisassembleError> {
         unsafe {
-            // Setup and disassemble the binary
-            let context = spvContextCreate(self.env.to_raw());
-    
+            // Disassemble the binary
             let (err_code, text, diag) = {
                 let mut out_text: spv_text = ptr::null_mut();
                 let mut out_diag = ptr::null_mut();
     
                 let result = if self.include_diagnostics {
                     spvBinaryToText(
-                        context,
+                        self.raw,
                         binary.as_ptr(),
                         binary.len(),
                         options.into_raw(),
@@ -383,7 +915,7 @@ impl Context {
                 }
                 else {
                     spvBinaryToText(
-                        context,
+                        self.raw,
                         binary.as_ptr(),
                         binary.len(),
                         options.into_raw(),
@@ -396,20 +928,14 @@ impl Context {
             };
     
             let result = match err_code {
-                spv_result_t::SUCCESS => {
-                    let bytes = slice::from_raw_parts(
-                        (*text).string as *const u8, 
-                        (*text).length
-                    );
-    
-                    let text = str::from_utf8(bytes)
-                        .and_then(|x| Ok(x.to_owned()))
-                        .expect("Spirv Tools returned an invalid encoding!");
-    
-                    Ok(text)
-                },
+                spv_result_t::SUCCESS => Ok(SpvTextBuf::from_raw(text)),
                 _                     => {
                     let (err, diag) = SpvError::from_raw(err_code, diag);
+
+                    if !text.is_null() {
+                        spvTextDestroy(text);
+                    }
+
                     Err(DisassembleError::SpirvTools(err, diag))
                 }
             };
@@ -418,29 +944,42 @@ impl Context {
                 spvDiagnosticDestroy(diag);
             }
     
-            if !text.is_null() {
-                spvTextDestroy(text);
-            }
-    
-            spvContextDestroy(context);
+            MessageConsumer::resume_panic();
     
             result
         }
     }
 
+    /// Read a spirv binary of either byte order from a file and disassemble it
+    /// using default options
+    pub fn disassemble_file<P: AsRef<Path>>(&self, path: P) -> Result<SpvTextBuf, DisassembleError> {
+        let binary = SpirvBinary::read(path)
+            .map_err(DisassembleError::Binary)?;
+
+        self.disassemble(&binary)
+    }
+
     /// Validate a spirv binary with the default options
     #[inline]
     pub fn validate(&self, binary: &[u32]) -> Result<(), ValidateError> {
-        self.validate_with_options(binary, ValidatorOptions::default())
+        self.validate_with_options(binary, &ValidatorOptions::default())
+    }
+
+    /// Read a spirv binary of either byte order from a file and validate it with
+    /// the default options
+    pub fn validate_file<P: AsRef<Path>>(&self, path: P) -> Result<(), ValidateError> {
+        let binary = SpirvBinary::read(path)
+            .map_err(ValidateError::Binary)?;
+
+        self.validate(&binary)
     }
 
     /// Validate a spirv binary with a set of options
-    pub fn validate_with_options(&self, binary: &[u32], options: ValidatorOptions) -> Result<(), ValidateError> {
+    pub fn validate_with_options(&self, binary: &[u32], options: &ValidatorOptions) -> Result<(), ValidateError> {
         unsafe {
-            let context = spvContextCreate(self.env.to_raw());
             let mut binary = spv_const_binary_t {
                 code: binary.as_ptr(),
-                word_count: binary.len()
+                wordCount: binary.len()
             };
     
             let (err_code, diag) = {
@@ -448,7 +987,7 @@ impl Context {
                 
                 let result = if self.include_diagnostics {
                     spvValidateWithOptions(
-                        context,
+                        self.raw,
                         options.raw,
                         &mut binary as spv_const_binary,
                         &mut out_diag as *mut spv_diagnostic
@@ -456,7 +995,7 @@ impl Context {
                 }
                 else {
                     spvValidateWithOptions(
-                        context,
+                        self.raw,
                         options.raw,
                         &mut binary as spv_const_binary,
                         ptr::null_mut()
@@ -478,9 +1017,122 @@ impl Context {
                 spvDiagnosticDestroy(diag);
             }
     
-            spvContextDestroy(context);
+            MessageConsumer::resume_panic();
     
             result
         }
     }
+
+    /// Validate a spirv binary, collecting every error and warning the validator
+    /// reports instead of only the first error.
+    ///
+    /// The warnings are returned if the binary is valid. Every message is passed to
+    /// the message consumer as well, even with diagnostics included. spirv-tools
+    /// stops most checks at the first error they find, so there is usually one
+    /// error along with the warnings found before it.
+    pub fn validate_all(&self, binary: &[u32], options: &ValidatorOptions) -> Result<Vec<Diagnostic>, ValidateError> {
+        // Without a diagnostic to fill in, spirv-tools reports every message to
+        // the consumer
+        let (err_code, messages) = MessageConsumer::collect(|| unsafe {
+            let mut binary = spv_const_binary_t {
+                code: binary.as_ptr(),
+                wordCount: binary.len()
+            };
+
+            spvValidateWithOptions(self.raw, options.raw, &mut binary as spv_const_binary, ptr::null_mut())
+        });
+
+        MessageConsumer::resume_panic();
+
+        let failure = match err_code {
+            spv_result_t::SUCCESS => None,
+            _                     => Some(unsafe { SpvError::from_raw(err_code, ptr::null_mut()) }.0)
+        };
+
+        let offsets = instruction_offsets(binary);
+        let diagnostics: Vec<_> = messages.into_iter()
+            .map(|x| Diagnostic::from_message(x, &offsets, failure.as_ref()))
+            .collect();
+
+        match failure {
+            Some(err) => Err(ValidateError::Failed { err, diagnostics }),
+            None      => Ok(diagnostics)
+        }
+    }
+
+    /// Parse a spirv binary, calling `header` once with the module header and
+    /// then `instruction` for every instruction in the module.
+    ///
+    /// Either callback can stop parsing early by returning `ControlFlow::Break`,
+    /// which is not treated as an error. A panic in a callback stops parsing and
+    /// is resumed once spirv-tools has returned.
+    pub fn parse<H, I>(&self, binary: &[u32], header: H, instruction: I) -> Result<(), ParseError>
+    where
+        H: FnMut(ParsedHeader) -> ControlFlow<()>,
+        I: FnMut(&ParsedInstruction) -> ControlFlow<()>
+    {
+        unsafe {
+            let mut callbacks = ParseCallbacks::new(header, instruction);
+            let mut out_diag = ptr::null_mut();
+
+            let err_code = spvBinaryParse(
+                self.raw,
+                &mut callbacks as *mut ParseCallbacks<H, I> as *mut libc::c_void,
+                binary.as_ptr(),
+                binary.len(),
+                callbacks.header_callback(),
+                callbacks.instruction_callback(),
+                if self.include_diagnostics { &mut out_diag as *mut spv_diagnostic } else { ptr::null_mut() }
+            );
+
+            let result = match err_code {
+                spv_result_t::SUCCESS               => Ok(()),
+                spv_result_t::REQUESTED_TERMINATION => Ok(()),
+                _                                   => {
+                    let (err, diag) = SpvError::from_raw(err_code, out_diag);
+                    Err(ParseError::SpirvTools(err, diag))
+                }
+            };
+
+            if !out_diag.is_null() {
+                spvDiagnosticDestroy(out_diag);
+            }
+
+            MessageConsumer::resume_panic();
+            callbacks.resume_panic();
+
+            result
+        }
+    }
+}
+
+impl Drop for Context {
+    fn drop(&mut self) {
+        unsafe { spvrsContextDestroy(self.handle); }
+    }
+}
+
+/// The word offset of every instruction in a binary, as far as the word counts of
+/// the instructions can be followed
+fn instruction_offsets(binary: &[u32]) -> Vec<usize> {
+    let swap = match ModuleHeader::parse(binary) {
+        Ok(header) => header.endianness != Endianness::NATIVE,
+        Err(_)     => return Vec::new()
+    };
+
+    let mut offsets = Vec::new();
+    let mut offset = ModuleHeader::WORDS;
+    while let Some(&word) = binary.get(offset) {
+        let word = if swap { word.swap_bytes() } else { word };
+        offsets.push(offset);
+
+        // The word count is invalid, so the next instruction can't be found
+        if word >> 16 == 0 {
+            break;
+        }
+
+        offset += (word >> 16) as usize;
+    }
+
+    offsets
 }
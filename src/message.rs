@@ -0,0 +1,329 @@
+use std::any::Any;
+use std::cell::RefCell;
+use std::ffi::CStr;
+use std::fmt::{self, Display};
+use std::mem;
+use std::panic::{self, AssertUnwindSafe};
+use std::sync::{Arc, Mutex, PoisonError};
+
+use libc::{c_char, c_void};
+
+use crate::error::SpvError;
+use crate::raw::*;
+
+/// The severity of a message reported by spirv-tools
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum MessageLevel {
+    /// Unrecoverable error due to the environment, such as running out of memory
+    Fatal,
+    /// Unrecoverable error due to spirv-tools internals, such as an
+    /// unimplemented feature
+    InternalError,
+    /// Normal error due to user input
+    Error,
+    /// Warning information
+    Warning,
+    /// General information
+    Info,
+    /// Debug information
+    Debug
+}
+
+impl MessageLevel {
+    /// Convert an `spv_message_level_t` into a `MessageLevel`
+    fn from_raw(level: spv_message_level_t) -> Self {
+        match level {
+            spv_message_level_t::FATAL          => MessageLevel::Fatal,
+            spv_message_level_t::INTERNAL_ERROR => MessageLevel::InternalError,
+            spv_message_level_t::ERROR          => MessageLevel::Error,
+            spv_message_level_t::WARNING        => MessageLevel::Warning,
+            spv_message_level_t::INFO           => MessageLevel::Info,
+            _                                   => MessageLevel::Debug
+        }
+    }
+
+    /// Check if the message reports an error of any kind
+    pub fn is_error(self) -> bool {
+        matches!(self, MessageLevel::Fatal | MessageLevel::InternalError | MessageLevel::Error)
+    }
+}
+
+impl Display for MessageLevel {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let name = match self {
+            MessageLevel::Fatal         => "fatal",
+            MessageLevel::InternalError => "internal error",
+            MessageLevel::Error         => "error",
+            MessageLevel::Warning       => "warning",
+            MessageLevel::Info          => "info",
+            MessageLevel::Debug         => "debug"
+        };
+
+        write!(f, "{}", name)
+    }
+}
+
+/// A location in the input a message refers to.
+///
+/// Text sources use the line and column, binaries the word index. All of them
+/// are zero based.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub struct Position {
+    pub line: usize,
+    pub column: usize,
+    pub index: usize
+}
+
+impl Position {
+    /// Create a position from it's raw representation
+    pub(crate) fn from_raw(position: &spv_position_t) -> Self {
+        Self {
+            line: position.line,
+            column: position.column,
+            index: position.index
+        }
+    }
+}
+
+/// A message reported by spirv-tools while processing a module
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Message {
+    level: MessageLevel,
+    source: String,
+    position: Position,
+    message: String
+}
+
+impl Message {
+    /// Create a message from the arguments passed to a message consumer
+    ///
+    /// # Safety
+    /// Assumes that the pointers are either null or valid
+    unsafe fn from_raw(
+        level: spv_message_level_t,
+        source: *const c_char,
+        position: *const spv_position_t,
+        message: *const c_char
+    ) -> Self {
+        let to_string = |x: *const c_char| if x.is_null() {
+            String::new()
+        }
+        else {
+            CStr::from_ptr(x).to_string_lossy().into_owned()
+        };
+
+        Self {
+            level: MessageLevel::from_raw(level),
+            source: to_string(source),
+            position: position.as_ref().map(Position::from_raw).unwrap_or_default(),
+            message: to_string(message)
+        }
+    }
+
+    /// The severity of the message
+    pub fn level(&self) -> MessageLevel {
+        self.level
+    }
+
+    /// The source of the input the message refers to, usually empty
+    pub fn source(&self) -> &str {
+        &self.source
+    }
+
+    /// The position in the input the message refers to
+    pub fn position(&self) -> Position {
+        self.position
+    }
+
+    /// The text of the message
+    pub fn message(&self) -> &str {
+        &self.message
+    }
+}
+
+impl Display for Message {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}: {}", self.level, self.message)
+    }
+}
+
+/// An error or warning reported while validating a binary
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Diagnostic {
+    severity: MessageLevel,
+    category: Option<SpvError>,
+    word_offset: Option<usize>,
+    message: String
+}
+
+impl Diagnostic {
+    /// Create a diagnostic from a message reported by the validator
+    ///
+    /// The validator numbers instructions from 1 and uses 0 for messages about
+    /// the module as a whole. `instruction_offsets` has the word offset of every
+    /// instruction, and `failure` is the error the validation returned, if any.
+    pub(crate) fn from_message(message: Message, instruction_offsets: &[usize], failure: Option<&SpvError>) -> Self {
+        let word_offset = message.position().index
+            .checked_sub(1)
+            .and_then(|x| instruction_offsets.get(x).copied());
+
+        // Messages don't carry the result code they were reported with, but
+        // warnings are always reported as one
+        let category = match message.level() {
+            MessageLevel::Warning       => Some(SpvError::Warning),
+            x if x.is_error()           => failure.cloned(),
+            _                           => None
+        };
+
+        Self {
+            severity: message.level(),
+            category,
+            word_offset,
+            message: message.message
+        }
+    }
+
+    /// The severity of the diagnostic
+    pub fn severity(&self) -> MessageLevel {
+        self.severity
+    }
+
+    /// The category of the diagnostic, or `None` for informational messages.
+    ///
+    /// spirv-tools returns a single category for a validation instead of one per
+    /// message. It stops most checks at the first error, so errors take the
+    /// category the validation failed with and warnings are `SpvError::Warning`.
+    pub fn category(&self) -> Option<&SpvError> {
+        self.category.as_ref()
+    }
+
+    /// The offset in words from the start of the binary of the instruction the
+    /// diagnostic refers to, or `None` if it is about the module as a whole
+    pub fn word_offset(&self) -> Option<usize> {
+        self.word_offset
+    }
+
+    /// The text of the diagnostic
+    pub fn message(&self) -> &str {
+        &self.message
+    }
+}
+
+// Formats as `<severity> at word <offset>: <message>`, leaving out the offset if
+// there is none
+impl Display for Diagnostic {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self.word_offset {
+            Some(offset)    => write!(f, "{} at word {}: {}", self.severity, offset, self.message),
+            None            => write!(f, "{}: {}", self.severity, self.message)
+        }
+    }
+}
+
+type ConsumerFn = Mutex<Box<dyn FnMut(Message) + Send>>;
+
+thread_local! {
+    /// A panic raised by a consumer on this thread, waiting to be resumed once the
+    /// spirv-tools call that reported the message returns
+    static CONSUMER_PANIC: RefCell<Option<Box<dyn Any + Send>>> = const { RefCell::new(None) };
+
+    /// Messages reported on this thread while a call is collecting them
+    static COLLECTED_MESSAGES: RefCell<Option<Vec<Message>>> = const { RefCell::new(None) };
+
+    /// The consumers currently running on this thread, by their user data
+    static RUNNING_CONSUMERS: RefCell<Vec<*mut c_void>> = const { RefCell::new(Vec::new()) };
+}
+
+/// A message consumer installed on a context or optimizer.
+///
+/// Messages are reported on the thread that invoked spirv-tools, so operations
+/// running in parallel take turns calling the consumer. Panics can't unwind
+/// through spirv-tools, so they are caught and resumed with `resume_panic`
+/// once the call into spirv-tools has returned.
+///
+/// A consumer that calls back into the context or optimizer it is installed on
+/// is not passed the messages of that nested call, as it is still running.
+///
+/// Clones share the same closure.
+#[derive(Clone)]
+pub(crate) struct MessageConsumer {
+    consumer: Arc<ConsumerFn>
+}
+
+impl MessageConsumer {
+    /// Wrap a closure to be called from spirv-tools
+    pub(crate) fn new<F: FnMut(Message) + Send + 'static>(consumer: F) -> Self {
+        Self {
+            consumer: Arc::new(Mutex::new(Box::new(consumer)))
+        }
+    }
+
+    /// The callback to pass to spirv-tools
+    pub(crate) fn callback(&self) -> spvrs_message_consumer {
+        Some(consume_message)
+    }
+
+    /// The user data to pass to spirv-tools along with the callback. Stays valid
+    /// for as long as the consumer is alive.
+    pub(crate) fn user_data(&self) -> *mut c_void {
+        &*self.consumer as *const ConsumerFn as *mut c_void
+    }
+
+    /// Run a call into spirv-tools, collecting a copy of every message it reports
+    /// to an installed consumer on this thread
+    pub(crate) fn collect<R, F: FnOnce() -> R>(call: F) -> (R, Vec<Message>) {
+        let outer = COLLECTED_MESSAGES.with(|x| x.borrow_mut().replace(Vec::new()));
+        let result = call();
+        let messages = COLLECTED_MESSAGES.with(|x| mem::replace(&mut *x.borrow_mut(), outer));
+
+        (result, messages.unwrap_or_default())
+    }
+
+    /// Resume a panic raised by a consumer during the last call into spirv-tools
+    pub(crate) fn resume_panic() {
+        if let Some(payload) = CONSUMER_PANIC.with(|x| x.borrow_mut().take()) {
+            panic::resume_unwind(payload);
+        }
+    }
+}
+
+/// Forwards messages from spirv-tools to the consumer in `user_data`
+unsafe extern "C" fn consume_message(
+    level: spv_message_level_t,
+    source: *const c_char,
+    position: *const spv_position_t,
+    message: *const c_char,
+    user_data: *mut c_void
+) {
+    // Once a consumer panicked the remaining messages of the call are dropped
+    if CONSUMER_PANIC.with(|x| x.borrow().is_some()) {
+        return;
+    }
+
+    let consumer = &*(user_data as *const ConsumerFn);
+    let message = Message::from_raw(level, source, position, message);
+
+    COLLECTED_MESSAGES.with(|x| if let Some(messages) = x.borrow_mut().as_mut() {
+        messages.push(message.clone());
+    });
+
+    // Calling the consumer again from inside itself would deadlock on its lock
+    if RUNNING_CONSUMERS.with(|x| x.borrow().contains(&user_data)) {
+        return;
+    }
+
+    RUNNING_CONSUMERS.with(|x| x.borrow_mut().push(user_data));
+
+    let result = panic::catch_unwind(AssertUnwindSafe(|| {
+        let mut consumer = consumer.lock()
+            .unwrap_or_else(PoisonError::into_inner);
+
+        consumer(message)
+    }));
+
+    RUNNING_CONSUMERS.with(|x| x.borrow_mut().pop());
+
+    if let Err(payload) = result {
+        CONSUMER_PANIC.with(|x| *x.borrow_mut() = Some(payload));
+    }
+}
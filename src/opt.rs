@@ -1,62 +1,116 @@
 use std::ffi::CString;
+use std::path::Path;
 use std::ptr;
 
-use crate::{TargetEnv, ValidatorOptions};
+use crate::{OptimizerPass, SpirvBinary, SpvBinaryBuf, TargetEnv, ValidatorOptions};
 use crate::error::*;
+use crate::message::*;
 use crate::raw::*;
 
-/// A set of options for configuring an optimizer pass 
+/// A set of options for configuring an optimizer pass.
+///
+/// The options can be shared by any number of runs, on any thread. Clones are
+/// independent copies of the options set so far.
 pub struct OptimizerOptions {
-    raw: spv_optimizer_options
+    raw: spv_optimizer_options,
+    // The settings are recorded so clones can replay them
+    run_validator: Option<bool>,
+    validator_options: Option<ValidatorOptions>,
+    max_id_bound: Option<u32>,
+    preserve_bindings: Option<bool>,
+    preserve_spec_constants: Option<bool>
 }
 
+// Options are only modified by value and read by the runs they are passed to
+unsafe impl Send for OptimizerOptions {}
+unsafe impl Sync for OptimizerOptions {}
+
 impl OptimizerOptions {
     /// Create a new optimizer options
     pub fn new() -> Self {
         Self {
-            raw: unsafe { spvOptimizerOptionsCreate() }
+            raw: unsafe { spvOptimizerOptionsCreate() },
+            run_validator: None,
+            validator_options: None,
+            max_id_bound: None,
+            preserve_bindings: None,
+            preserve_spec_constants: None
         }
     }
     
     /// Records whether or not the optimizer should run the validator before
     /// optimizing.  If |val| is true, the validator will be run.
-    pub fn run_validator(self, value: bool) -> Self {
+    pub fn run_validator(mut self, value: bool) -> Self {
         unsafe { spvOptimizerOptionsSetRunValidator(self.raw, value); }
+        self.run_validator = Some(value);
         self
     } 
 
     /// Records the validator options that should be passed to the validator if it is
     /// run.
-    pub fn validator_options(self, options: ValidatorOptions) -> Self {
+    pub fn validator_options(mut self, options: &ValidatorOptions) -> Self {
         unsafe { spvOptimizerOptionsSetValidatorOptions(self.raw, options.raw); }
+        self.validator_options = Some(options.clone());
         self
     }
 
     /// Records the maximum possible value for the id bound.
-    pub fn max_id_bound(self, value: u32) -> Self {
+    pub fn max_id_bound(mut self, value: u32) -> Self {
         unsafe { spvOptimizerOptionsSetMaxIdBound(self.raw, value); }
+        self.max_id_bound = Some(value);
         self
     }
 
     /// Records whether all bindings within the module should be preserved.
-    pub fn preserve_bindings(self, value: bool) -> Self {
+    pub fn preserve_bindings(mut self, value: bool) -> Self {
         unsafe { spvOptimizerOptionsSetPreserveBindings(self.raw, value); }
+        self.preserve_bindings = Some(value);
         self
     }
 
     /// Records whether all specialization constants within the module
     /// should be preserved.
-    pub fn preserve_spec_constants(self, value: bool) -> Self {
+    pub fn preserve_spec_constants(mut self, value: bool) -> Self {
         unsafe { spvOptimizerOptionsSetPreserveSpecConstants(self.raw, value); }
+        self.preserve_spec_constants = Some(value);
         self
     }
 
     /// Convert the optimizer options into it's raw representation
-    fn to_raw(self) -> spv_optimizer_options {
+    fn to_raw(&self) -> spv_optimizer_options {
         self.raw
     }
 }
 
+// Clones replay the recorded settings on new options
+impl Clone for OptimizerOptions {
+    fn clone(&self) -> Self {
+        let mut options = OptimizerOptions::new();
+
+        if let Some(value) = self.run_validator {
+            options = options.run_validator(value);
+        }
+
+        if let Some(value) = &self.validator_options {
+            options = options.validator_options(value);
+        }
+
+        if let Some(value) = self.max_id_bound {
+            options = options.max_id_bound(value);
+        }
+
+        if let Some(value) = self.preserve_bindings {
+            options = options.preserve_bindings(value);
+        }
+
+        if let Some(value) = self.preserve_spec_constants {
+            options = options.preserve_spec_constants(value);
+        }
+
+        options
+    }
+}
+
 impl Drop for OptimizerOptions {
     fn drop(&mut self) {
         unsafe { spvOptimizerOptionsDestroy(self.raw); }
@@ -69,20 +123,88 @@ impl Default for OptimizerOptions {
     }
 }
 
-/// An optimizer instance for spirv binaries
+/// Passes registered with an optimizer, recorded so clones can replay them
+#[derive(Clone)]
+enum RegisteredPasses {
+    Flag(String),
+    Performance,
+    Size,
+    VulkanToWebGpu,
+    WebGpuToVulkan,
+    Legalization
+}
+
+/// An optimizer instance for spirv binaries.
+///
+/// An optimizer is configured once and can then run on any number of binaries,
+/// from any number of threads at once. Every run starts from a fresh copy of
+/// the registered passes, so the output doesn't depend on earlier runs. Clones
+/// replay the registered passes and share the message consumer.
 pub struct Optimizer {
-    optimizer: spv_optimizer
+    // Only used to check passes as they are registered. Passes keep state once
+    // they ran, so spirv-tools optimizers are never run more than once.
+    optimizer: spvrs_optimizer,
+    // A consumer is always installed so runs can collect their messages
+    consumer: MessageConsumer,
+    env: TargetEnv,
+    passes: Vec<RegisteredPasses>
 }
 
+// The optimizer is only modified through `&mut self` or by value. Runs through
+// `&self` only read the recorded passes and run on an instance of their own.
+unsafe impl Send for Optimizer {}
+unsafe impl Sync for Optimizer {}
+
 impl Optimizer {
     /// Create a new optimizer instance for a given environment
     pub fn new(env: TargetEnv) -> Self {
-        Self {
-            optimizer: unsafe { spvOptimizerCreate(env.to_raw()) }
-        }
+        let mut optimizer = Self {
+            optimizer: create_raw(env),
+            consumer: MessageConsumer::new(|_| {}),
+            env,
+            passes: Vec::new()
+        };
+
+        optimizer.install_consumer();
+        optimizer
     }
 
-    // TODO: Message consumers
+    /// Set a consumer for the messages reported while registering passes and
+    /// optimizing, replacing any previous consumer.
+    ///
+    /// A panic in the consumer is resumed once the call reporting the message
+    /// returns.
+    pub fn set_message_consumer<F: FnMut(Message) + Send + 'static>(&mut self, consumer: F) {
+        self.consumer = MessageConsumer::new(consumer);
+        self.install_consumer();
+    }
+
+    /// Remove the message consumer, ignoring all messages from now on. Messages
+    /// are still returned from the runs of the optimizer.
+    pub fn clear_message_consumer(&mut self) {
+        self.consumer = MessageConsumer::new(|_| {});
+        self.install_consumer();
+    }
+
+    /// Point spirv-tools at the current consumer
+    fn install_consumer(&mut self) {
+        unsafe { spvrsOptimizerSetMessageConsumer(self.optimizer, self.consumer.callback(), self.consumer.user_data()); }
+    }
+
+    /// Register an optimization pass. Fails if the linked spirv-tools does not
+    /// know the pass or rejects it's arguments, or if the arguments can't be
+    /// written as a flag, such as an empty list of pairs.
+    pub fn register_pass(self, pass: OptimizerPass) -> Result<Self, OptimizerError> {
+        let flag = pass.flag();
+
+        // Arguments that don't survive being written out would register a
+        // different pass than the one asked for
+        if flag.parse::<OptimizerPass>().ok().as_ref() != Some(&pass) {
+            return Err(OptimizerError::InvalidFlag(flag));
+        }
+
+        self.register_pass_from_flag(&flag)
+    }
 
     /// Register several optimization passes from a set of flags.
     /// 
@@ -111,22 +233,12 @@ impl Optimizer {
     ///
     /// --legalize-hlsl: Registers all passes that legalize SPIR-V generated by an
     ///                  HLSL front-end.
-    pub fn register_pass_from_flag(self, flag: &str) -> Result<Self, OptimizerError> {
-        unsafe {
-            let c_flag = CString::new(flag)
-                .expect("Invalid flag provided. Flags must be valid C strings");
-
-            let result = spvOptimizerRegisterPassFromFlag(
-                self.optimizer,
-                c_flag.as_ptr()
-            );
-
-            if !result {
-                Err(OptimizerError::InvalidFlag(flag.to_owned()))
-            }
-            else {
-                Ok(self)
-            }
+    pub fn register_pass_from_flag(mut self, flag: &str) -> Result<Self, OptimizerError> {
+        if self.register(RegisteredPasses::Flag(flag.to_owned())) {
+            Ok(self)
+        }
+        else {
+            Err(OptimizerError::InvalidFlag(flag.to_owned()))
         }
     }
 
@@ -138,44 +250,49 @@ impl Optimizer {
     ///
     /// If |flag| takes one of the forms above, it returns true.  Otherwise, it
     /// returns false.
+    /// Flags containing a nul byte are never valid.
     pub fn flag_has_valid_form(&self, flag: &str) -> bool {
-        unsafe {
-            let c_flag = CString::new(flag)
-                .expect("Invalid flag provided. Flags must be valid C strings");
-                
-            spvOptimizerFlagHasValidForm(c_flag.as_ptr())
+        match CString::new(flag) {
+            Ok(c_flag) => unsafe { spvrsOptimizerFlagHasValidForm(self.optimizer, c_flag.as_ptr()) },
+            Err(_)     => false
         }
     }
 
     /// Registers passes that attempt to improve performance of generated code.
     /// This sequence of passes is subject to constant review and will change
     /// from time to time.
-    pub fn register_performance_passes(self) -> Self {
-        unsafe { spvOptimizerRegisterPerformancePasses(self.optimizer); }
+    pub fn register_performance_passes(mut self) -> Self {
+        self.register(RegisteredPasses::Performance);
         self
     }
 
     /// Registers passes that attempt to improve the size of generated code.
     /// This sequence of passes is subject to constant review and will change
     /// from time to time.
-    pub fn register_size_passes(self) -> Self {
-        unsafe { spvOptimizerRegisterSizePasses(self.optimizer); }
+    pub fn register_size_passes(mut self) -> Self {
+        self.register(RegisteredPasses::Size);
         self
     }
 
     /// Registers passes that have been prescribed for converting from Vulkan to
     /// WebGPU. This sequence of passes is subject to constant review and will
     /// change from time to time.
-    pub fn register_vulkan_to_web_gpu_passes(self) -> Self {
-        unsafe { spvOptimizerRegisterVulkanToWebGPUPasses(self.optimizer); }
+    ///
+    /// Registers nothing if the linked spirv-tools dropped the recipe along with
+    /// its WebGPU support.
+    pub fn register_vulkan_to_web_gpu_passes(mut self) -> Self {
+        self.register(RegisteredPasses::VulkanToWebGpu);
         self
     }
 
     /// Registers passes that have been prescribed for converting from WebGPU to
     /// Vulkan. This sequence of passes is subject to constant review and will
     /// change from time to time.
-    pub fn register_web_gpu_to_vulkan_passes(self) -> Self {
-        unsafe { spvOptimizerRegisterWebGPUToVulkanPasses(self.optimizer); }
+    ///
+    /// Registers nothing if the linked spirv-tools dropped the recipe along with
+    /// its WebGPU support.
+    pub fn register_web_gpu_to_vulkan_passes(mut self) -> Self {
+        self.register(RegisteredPasses::WebGpuToVulkan);
         self
     }
 
@@ -187,71 +304,150 @@ impl Optimizer {
     ///
     /// This sequence of passes is subject to constant review and will change
     /// from time to time.
-    pub fn register_legalization_passes(self) -> Self {
-        unsafe { spvOptimizerRegisterLegalizationPasses(self.optimizer); }
+    pub fn register_legalization_passes(mut self) -> Self {
+        self.register(RegisteredPasses::Legalization);
         self
     }
 
+    /// Register passes with spirv-tools, recording them if they were accepted
+    fn register(&mut self, passes: RegisteredPasses) -> bool {
+        let registered = unsafe { register_raw(self.optimizer, &passes) };
+
+        MessageConsumer::resume_panic();
+
+        if registered {
+            self.passes.push(passes);
+        }
+
+        registered
+    }
+
     /// Change the target env from the one the optimizer was created with
     pub fn set_target_env(&mut self, env: TargetEnv) {
-        unsafe { spvOptimizerSetTargetEnv(self.optimizer, env.to_raw()); }
+        unsafe { spvrsOptimizerSetTargetEnv(self.optimizer, env.to_raw()); }
+        self.env = env;
     }
 
     /// Run the optimizer with it's current passes and default options on the provided binary
-    pub fn run(&self, binary: &[u32]) -> Result<Vec<u32>, OptimizerError> {
-        unsafe {
-            let bin_ptr = binary.as_ptr();
-            let bin_len = binary.len();
+    pub fn run(&self, binary: &[u32]) -> Result<SpvBinaryBuf, OptimizerError> {
+        self.run_raw(binary, None)
+            .map(|(optimized, _)| optimized)
+    }
 
-            let mut out_bin = ptr::null_mut();
-            if spvOptimizerRun(self.optimizer, bin_ptr, bin_len, &mut out_bin) {
-                let out_ptr = (*out_bin).code;
-                let out_len = (*out_bin).word_count;
-                let mut opt_bin = Vec::with_capacity(out_len);
-                
-                ptr::copy_nonoverlapping(out_ptr, opt_bin.as_mut_ptr(), out_len);
+    /// Read a spirv binary of either byte order from a file and run the optimizer
+    /// with it's current passes and default options on it
+    pub fn run_file<P: AsRef<Path>>(&self, path: P) -> Result<SpvBinaryBuf, OptimizerError> {
+        let binary = SpirvBinary::read(path)
+            .map_err(OptimizerError::Binary)?;
 
-                opt_bin.set_len(out_len);
+        self.run(&binary)
+    }
 
-                spvBinaryDestroy(out_bin);
+    /// Run the optimizer with it's current passes and the provided options on the provided binary
+    pub fn run_with_options(&self, binary: &[u32], options: &OptimizerOptions) -> Result<SpvBinaryBuf, OptimizerError> {
+        self.run_raw(binary, Some(options))
+            .map(|(optimized, _)| optimized)
+    }
 
-                Ok(opt_bin)
-            }
-            else {
-                Err(OptimizerError::OptimizationFailed())
-            }
-        }
+    /// Run the optimizer with it's current passes and the provided options on the
+    /// provided binary, also returning the warnings and other messages reported
+    /// by a successful run
+    pub fn run_with_messages(&self, binary: &[u32], options: &OptimizerOptions) -> Result<(SpvBinaryBuf, Vec<Message>), OptimizerError> {
+        self.run_raw(binary, Some(options))
     }
 
-    /// Run the optimizer with it's current passes and the provided options on the provided binary
-    pub fn run_with_options(&self, binary: &[u32], options: OptimizerOptions) -> Result<Vec<u32>, OptimizerError> {
+    /// Run the optimizer, collecting the messages reported during the run. Runs
+    /// without options use the optimizer's defaults.
+    fn run_raw(&self, binary: &[u32], options: Option<&OptimizerOptions>) -> Result<(SpvBinaryBuf, Vec<Message>), OptimizerError> {
         unsafe {
             let bin_ptr = binary.as_ptr();
             let bin_len = binary.len();
 
-            let mut out_bin = ptr::null_mut();
-            if spvOptimizerRunWithOptions(self.optimizer, bin_ptr, bin_len, &mut out_bin, options.to_raw()) {
-                let out_ptr = (*out_bin).code;
-                let out_len = (*out_bin).word_count;
-                let mut opt_bin = Vec::with_capacity(out_len);
-                
-                ptr::copy_nonoverlapping(out_ptr, opt_bin.as_mut_ptr(), out_len);
+            // Passes keep state between runs, which changes the output of later
+            // runs, so every run gets a fresh instance
+            let optimizer = self.replay();
+            spvrsOptimizerSetMessageConsumer(optimizer, self.consumer.callback(), self.consumer.user_data());
 
-                opt_bin.set_len(out_len);
+            let mut out_bin = ptr::null_mut();
+            let options = options.map_or(ptr::null_mut(), |x| x.to_raw());
+            let (succeeded, messages) = MessageConsumer::collect(|| {
+                spvrsOptimizerRun(optimizer, bin_ptr, bin_len, &mut out_bin, options)
+            });
 
-                spvBinaryDestroy(out_bin);
+            spvrsOptimizerDestroy(optimizer);
+            MessageConsumer::resume_panic();
 
-                Ok(opt_bin)
+            if succeeded {
+                Ok((SpvBinaryBuf::from_raw(out_bin), messages))
             }
             else {
-                Err(OptimizerError::OptimizationFailed())
+                if !out_bin.is_null() {
+                    spvBinaryDestroy(out_bin);
+                }
+
+                Err(OptimizerError::Failed { messages })
+            }
+        }
+    }
+
+    /// A new spirv-tools optimizer with the target environment and passes of
+    /// this one, which ignores all messages. Anything registering the passes
+    /// reports was already reported when they were first registered.
+    fn replay(&self) -> spvrs_optimizer {
+        unsafe {
+            let optimizer = create_raw(self.env);
+            spvrsOptimizerSetMessageConsumer(optimizer, None, ptr::null_mut());
+
+            for passes in &self.passes {
+                register_raw(optimizer, passes);
             }
+
+            optimizer
         }
     }
 }
 
+// Clones replay the target environment and passes on a new optimizer
+impl Clone for Optimizer {
+    fn clone(&self) -> Self {
+        let mut optimizer = Self {
+            optimizer: self.replay(),
+            consumer: self.consumer.clone(),
+            env: self.env,
+            passes: self.passes.clone()
+        };
+
+        optimizer.install_consumer();
+        optimizer
+    }
+}
+
 impl Drop for Optimizer {
     fn drop(&mut self) {
-        unsafe { spvOptimizerDestroy(self.optimizer); }
+        unsafe { spvrsOptimizerDestroy(self.optimizer); }
+    }
+}
+
+/// Create a spirv-tools optimizer for the target environment
+fn create_raw(env: TargetEnv) -> spvrs_optimizer {
+    let optimizer = unsafe { spvrsOptimizerCreate(env.to_raw()) };
+    assert!(!optimizer.is_null(), "Failed to create a spirv-tools optimizer");
+
+    optimizer
+}
+
+/// Register passes with a spirv-tools optimizer, returning whether they were
+/// accepted
+unsafe fn register_raw(optimizer: spvrs_optimizer, passes: &RegisteredPasses) -> bool {
+    match passes {
+        RegisteredPasses::Flag(flag)        => match CString::new(flag.as_str()) {
+            Ok(c_flag)  => spvrsOptimizerRegisterPassFromFlag(optimizer, c_flag.as_ptr()),
+            Err(_)      => false
+        },
+        RegisteredPasses::Performance       => { spvrsOptimizerRegisterPerformancePasses(optimizer); true },
+        RegisteredPasses::Size              => { spvrsOptimizerRegisterSizePasses(optimizer); true },
+        RegisteredPasses::VulkanToWebGpu    => spvrsOptimizerRegisterVulkanToWebGPUPasses(optimizer),
+        RegisteredPasses::WebGpuToVulkan    => spvrsOptimizerRegisterWebGPUToVulkanPasses(optimizer),
+        RegisteredPasses::Legalization      => { spvrsOptimizerRegisterLegalizationPasses(optimizer); true }
     }
 }
@@ -0,0 +1,221 @@
+use std::any::Any;
+use std::ops::ControlFlow;
+use std::panic::{self, AssertUnwindSafe};
+use std::slice;
+
+use libc::c_void;
+
+use crate::raw::*;
+
+/// Byte order of a SPIR-V module
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum Endianness {
+    Little,
+    Big
+}
+
+impl Endianness {
+    /// The byte order of the machine
+    pub const NATIVE: Self = if cfg!(target_endian = "little") { Endianness::Little } else { Endianness::Big };
+
+    /// Convert an `spv_endianness_t` into an `Endianness`
+    pub(crate) fn from_raw(endian: spv_endianness_t) -> Self {
+        match endian {
+            spv_endianness_t::BIG   => Endianness::Big,
+            _                       => Endianness::Little
+        }
+    }
+
+    /// The opposite byte order
+    pub(crate) fn swapped(self) -> Self {
+        match self {
+            Endianness::Little  => Endianness::Big,
+            Endianness::Big     => Endianness::Little
+        }
+    }
+}
+
+/// The header of a module as reported by the binary parser
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct ParsedHeader {
+    /// Byte order of the words in the binary
+    pub endianness: Endianness,
+    pub magic: u32,
+    pub version: u32,
+    pub generator: u32,
+    pub id_bound: u32,
+    /// Reserved schema word, currently always 0
+    pub schema: u32
+}
+
+/// An instruction parsed from a binary. Only valid for the duration of the
+/// callback it was passed to.
+pub struct ParsedInstruction<'a> {
+    raw: &'a spv_parsed_instruction_t
+}
+
+impl<'a> ParsedInstruction<'a> {
+    /// The opcode of the instruction
+    pub fn opcode(&self) -> u16 {
+        self.raw.opcode
+    }
+
+    /// All words of the instruction, including the opcode word, in native
+    /// endianness
+    pub fn words(&self) -> &'a [u32] {
+        unsafe { slice::from_raw_parts(self.raw.words, self.raw.num_words as usize) }
+    }
+
+    /// The result id, if the instruction has one
+    pub fn result_id(&self) -> Option<u32> {
+        Some(self.raw.result_id).filter(|x| *x != 0)
+    }
+
+    /// The type id, if the instruction has one
+    pub fn type_id(&self) -> Option<u32> {
+        Some(self.raw.type_id).filter(|x| *x != 0)
+    }
+
+    /// The extended instruction set used by an `OpExtInst`, `NONE` for any other
+    /// instruction
+    pub fn ext_inst_type(&self) -> spv_ext_inst_type_t {
+        self.raw.ext_inst_type
+    }
+
+    /// The operands of the instruction
+    pub fn operands(&self) -> impl ExactSizeIterator<Item = ParsedOperand<'a>> + 'a {
+        let operands: &'a [spv_parsed_operand_t] = if self.raw.num_operands == 0 {
+            &[]
+        }
+        else {
+            unsafe { slice::from_raw_parts(self.raw.operands, self.raw.num_operands as usize) }
+        };
+
+        let words = self.words();
+        operands.iter()
+            .map(move |raw| ParsedOperand { raw, words })
+    }
+}
+
+/// An operand of a parsed instruction
+pub struct ParsedOperand<'a> {
+    raw: &'a spv_parsed_operand_t,
+    words: &'a [u32]
+}
+
+impl<'a> ParsedOperand<'a> {
+    /// The concrete type of the operand
+    pub fn operand_type(&self) -> spv_operand_type_t {
+        self.raw.type_
+    }
+
+    /// For literal numbers, whether it is a signed integer, an unsigned integer
+    /// or a floating point number
+    pub fn number_kind(&self) -> spv_number_kind_t {
+        self.raw.number_kind
+    }
+
+    /// The number of bits of a literal number
+    pub fn number_bit_width(&self) -> u32 {
+        self.raw.number_bit_width
+    }
+
+    /// Location of the operand in words from the start of the instruction
+    pub fn offset(&self) -> usize {
+        self.raw.offset as usize
+    }
+
+    /// The words occupied by the operand
+    pub fn words(&self) -> &'a [u32] {
+        let start = self.raw.offset as usize;
+        &self.words[start..start + self.raw.num_words as usize]
+    }
+}
+
+/// The callbacks of a running parse, passed to spirv-tools as user data
+pub(crate) struct ParseCallbacks<H, I> {
+    header: H,
+    instruction: I,
+    panic: Option<Box<dyn Any + Send>>
+}
+
+impl<H, I> ParseCallbacks<H, I>
+where
+    H: FnMut(ParsedHeader) -> ControlFlow<()>,
+    I: FnMut(&ParsedInstruction) -> ControlFlow<()>
+{
+    pub(crate) fn new(header: H, instruction: I) -> Self {
+        Self {
+            header,
+            instruction,
+            panic: None
+        }
+    }
+
+    pub(crate) fn header_callback(&self) -> spv_parsed_header_fn_t {
+        Some(parse_header::<H, I>)
+    }
+
+    pub(crate) fn instruction_callback(&self) -> spv_parsed_instruction_fn_t {
+        Some(parse_instruction::<H, I>)
+    }
+
+    /// Resume a panic raised by one of the callbacks
+    pub(crate) fn resume_panic(self) {
+        if let Some(payload) = self.panic {
+            panic::resume_unwind(payload);
+        }
+    }
+
+    /// Run a callback, stopping the parse if it asks to or panics
+    fn invoke<F: FnOnce(&mut Self) -> ControlFlow<()>>(&mut self, callback: F) -> spv_result_t {
+        match panic::catch_unwind(AssertUnwindSafe(|| callback(self))) {
+            Ok(ControlFlow::Continue(())) => spv_result_t::SUCCESS,
+            Ok(ControlFlow::Break(()))    => spv_result_t::REQUESTED_TERMINATION,
+            Err(payload)                  => {
+                self.panic = Some(payload);
+                spv_result_t::REQUESTED_TERMINATION
+            }
+        }
+    }
+}
+
+unsafe extern "C" fn parse_header<H, I>(
+    user_data: *mut c_void,
+    endian: spv_endianness_t,
+    magic: u32,
+    version: u32,
+    generator: u32,
+    id_bound: u32,
+    reserved: u32
+) -> spv_result_t
+where
+    H: FnMut(ParsedHeader) -> ControlFlow<()>,
+    I: FnMut(&ParsedInstruction) -> ControlFlow<()>
+{
+    let callbacks = &mut *(user_data as *mut ParseCallbacks<H, I>);
+    let header = ParsedHeader {
+        endianness: Endianness::from_raw(endian),
+        magic,
+        version,
+        generator,
+        id_bound,
+        schema: reserved
+    };
+
+    callbacks.invoke(|x| (x.header)(header))
+}
+
+unsafe extern "C" fn parse_instruction<H, I>(
+    user_data: *mut c_void,
+    parsed_instruction: *const spv_parsed_instruction_t
+) -> spv_result_t
+where
+    H: FnMut(ParsedHeader) -> ControlFlow<()>,
+    I: FnMut(&ParsedInstruction) -> ControlFlow<()>
+{
+    let callbacks = &mut *(user_data as *mut ParseCallbacks<H, I>);
+    let instruction = ParsedInstruction { raw: &*parsed_instruction };
+
+    callbacks.invoke(|x| (x.instruction)(&instruction))
+}
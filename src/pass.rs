@@ -0,0 +1,438 @@
+use std::fmt::{self, Display};
+use std::str::FromStr;
+
+use crate::error::OptimizerError;
+
+/// An optimization pass, or recipe of passes, that can be registered with an
+/// `Optimizer`.
+///
+/// Each pass corresponds to a flag accepted by `Optimizer::register_pass_from_flag`,
+/// which `Display` produces and `FromStr` parses. Passes are only available if the
+/// linked spirv-tools knows them, unknown passes fail to register.
+#[derive(Clone, Debug, PartialEq)]
+#[non_exhaustive]
+pub enum OptimizerPass {
+    /// `-O`, all performance passes
+    Performance,
+    /// `-Os`, all size passes
+    Size,
+    /// `--legalize-hlsl`, all passes legalizing SPIR-V generated from HLSL
+    LegalizeHlsl,
+
+    /// `--strip-debug`
+    StripDebugInfo,
+    /// `--strip-reflect`
+    StripReflectInfo,
+    /// `--strip-nonsemantic`
+    StripNonSemanticInfo,
+    /// `--set-spec-const-default-value`, setting the default value of
+    /// specialization constants. Needs at least one pair, and values can't
+    /// contain whitespace or nul bytes.
+    ///
+    /// (spec id and default value pairs)
+    SetSpecConstantDefaultValue(Vec<(u32, String)>),
+    /// `--if-conversion`
+    IfConversion,
+    /// `--freeze-spec-const`
+    FreezeSpecConstantValue,
+    /// `--inline-entry-points-exhaustive`
+    InlineEntryPointsExhaustive,
+    /// `--inline-entry-points-opaque`
+    InlineEntryPointsOpaque,
+    /// `--combine-access-chains`
+    CombineAccessChains,
+    /// `--convert-local-access-chains`
+    ConvertLocalAccessChains,
+    /// `--replace-desc-array-access-using-var-index`
+    ReplaceDescArrayAccessUsingVarIndex,
+    /// `--spread-volatile-semantics`
+    SpreadVolatileSemantics,
+    /// `--descriptor-scalar-replacement`
+    DescriptorScalarReplacement,
+    /// `--eliminate-dead-code-aggressive`
+    EliminateDeadCode,
+    /// `--eliminate-insert-extract`
+    EliminateInsertExtract,
+    /// `--eliminate-local-single-block`
+    EliminateLocalSingleBlock,
+    /// `--eliminate-local-single-store`
+    EliminateLocalSingleStore,
+    /// `--merge-blocks`
+    MergeBlocks,
+    /// `--merge-return`
+    MergeReturn,
+    /// `--eliminate-dead-branches`
+    EliminateDeadBranches,
+    /// `--eliminate-dead-functions`
+    EliminateDeadFunctions,
+    /// `--eliminate-local-multi-store`
+    EliminateLocalMultiStore,
+    /// `--eliminate-dead-const`
+    EliminateDeadConstant,
+    /// `--eliminate-dead-inserts`
+    EliminateDeadInserts,
+    /// `--eliminate-dead-variables`
+    EliminateDeadVariables,
+    /// `--eliminate-dead-members`
+    EliminateDeadMembers,
+    /// `--fold-spec-const-op-composite`
+    FoldSpecConstantOpComposite,
+    /// `--loop-unswitch`
+    LoopUnswitch,
+    /// `--scalar-replacement`, only replacing composites with at most `limit`
+    /// members. `None` uses the spirv-tools default, `Some(0)` removes the limit.
+    ScalarReplacement { limit: Option<u32> },
+    /// `--strength-reduction`
+    StrengthReduction,
+    /// `--unify-const`
+    UnifyConstant,
+    /// `--flatten-decorations`
+    FlattenDecorations,
+    /// `--compact-ids`
+    CompactIds,
+    /// `--cfg-cleanup`
+    CfgCleanup,
+    /// `--local-redundancy-elimination`
+    LocalRedundancyElimination,
+    /// `--loop-invariant-code-motion`
+    LoopInvariantCodeMotion,
+    /// `--reduce-load-size`, replacing loads of composites when less than
+    /// `threshold` of the components are used. `None` uses the spirv-tools
+    /// default, the threshold has to be finite and not negative.
+    ReduceLoadSize { threshold: Option<f64> },
+    /// `--redundancy-elimination`
+    RedundancyElimination,
+    /// `--private-to-local`
+    PrivateToLocal,
+    /// `--remove-duplicates`
+    RemoveDuplicates,
+    /// `--workaround-1209`
+    Workaround1209,
+    /// `--replace-invalid-opcode`
+    ReplaceInvalidOpcode,
+    /// `--inst-bindless-check`
+    InstBindlessCheck,
+    /// `--inst-desc-idx-check`
+    InstDescIdxCheck,
+    /// `--inst-buff-oob-check`
+    InstBuffOobCheck,
+    /// `--inst-buff-addr-check`
+    InstBuffAddrCheck,
+    /// `--convert-relaxed-to-half`
+    ConvertRelaxedToHalf,
+    /// `--relax-float-ops`
+    RelaxFloatOps,
+    /// `--inst-debug-printf`
+    InstDebugPrintf,
+    /// `--simplify-instructions`
+    SimplifyInstructions,
+    /// `--ssa-rewrite`
+    SsaRewrite,
+    /// `--copy-propagate-arrays`
+    CopyPropagateArrays,
+    /// `--loop-fission`, splitting loops using more than `register_threshold`
+    /// registers
+    LoopFission { register_threshold: u32 },
+    /// `--loop-fusion`, fusing loops if the result uses at most `max_registers`
+    /// registers
+    LoopFusion { max_registers: u32 },
+    /// `--loop-unroll`, fully unrolling loops
+    LoopUnroll,
+    /// `--loop-unroll-partial`, unrolling loops by `factor`
+    LoopUnrollPartial { factor: u32 },
+    /// `--upgrade-memory-model`
+    UpgradeMemoryModel,
+    /// `--vector-dce`
+    VectorDce,
+    /// `--loop-peeling`
+    LoopPeeling,
+    /// `--loop-peeling-threshold`, setting the code size threshold of loop
+    /// peeling for the whole process
+    ///
+    /// (threshold)
+    LoopPeelingThreshold(u32),
+    /// `--ccp`, conditional constant propagation
+    Ccp,
+    /// `--code-sink`
+    CodeSink,
+    /// `--fix-storage-class`
+    FixStorageClass,
+    /// `--remove-unused-interface-variables`
+    RemoveUnusedInterfaceVariables,
+    /// `--graphics-robust-access`
+    GraphicsRobustAccess,
+    /// `--wrap-opkill`
+    WrapOpKill,
+    /// `--amd-ext-to-khr`
+    AmdExtToKhr,
+    /// `--interpolate-fixup`
+    InterpolateFixup,
+    /// `--remove-dont-inline`
+    RemoveDontInline,
+    /// `--eliminate-dead-input-components`
+    EliminateDeadInputComponents,
+    /// `--fix-func-call-param`
+    FixFuncCallParam,
+    /// `--convert-to-sampled-image`, converting the images at the given bindings
+    /// to sampled images. Needs at least one pair.
+    ///
+    /// (descriptor set and binding pairs)
+    ConvertToSampledImage(Vec<(u32, u32)>)
+}
+
+impl OptimizerPass {
+    /// The flag registering the pass, e.g. `--scalar-replacement=100`
+    pub fn flag(&self) -> String {
+        self.to_string()
+    }
+
+    /// The name of the pass, the flag without dashes and arguments
+    pub fn name(&self) -> &'static str {
+        match self {
+            OptimizerPass::Performance                          => "O",
+            OptimizerPass::Size                                 => "Os",
+            OptimizerPass::LegalizeHlsl                         => "legalize-hlsl",
+            OptimizerPass::StripDebugInfo                       => "strip-debug",
+            OptimizerPass::StripReflectInfo                     => "strip-reflect",
+            OptimizerPass::StripNonSemanticInfo                 => "strip-nonsemantic",
+            OptimizerPass::SetSpecConstantDefaultValue(_)       => "set-spec-const-default-value",
+            OptimizerPass::IfConversion                         => "if-conversion",
+            OptimizerPass::FreezeSpecConstantValue              => "freeze-spec-const",
+            OptimizerPass::InlineEntryPointsExhaustive          => "inline-entry-points-exhaustive",
+            OptimizerPass::InlineEntryPointsOpaque              => "inline-entry-points-opaque",
+            OptimizerPass::CombineAccessChains                  => "combine-access-chains",
+            OptimizerPass::ConvertLocalAccessChains             => "convert-local-access-chains",
+            OptimizerPass::ReplaceDescArrayAccessUsingVarIndex  => "replace-desc-array-access-using-var-index",
+            OptimizerPass::SpreadVolatileSemantics              => "spread-volatile-semantics",
+            OptimizerPass::DescriptorScalarReplacement          => "descriptor-scalar-replacement",
+            OptimizerPass::EliminateDeadCode                    => "eliminate-dead-code-aggressive",
+            OptimizerPass::EliminateInsertExtract               => "eliminate-insert-extract",
+            OptimizerPass::EliminateLocalSingleBlock            => "eliminate-local-single-block",
+            OptimizerPass::EliminateLocalSingleStore            => "eliminate-local-single-store",
+            OptimizerPass::MergeBlocks                          => "merge-blocks",
+            OptimizerPass::MergeReturn                          => "merge-return",
+            OptimizerPass::EliminateDeadBranches                => "eliminate-dead-branches",
+            OptimizerPass::EliminateDeadFunctions               => "eliminate-dead-functions",
+            OptimizerPass::EliminateLocalMultiStore             => "eliminate-local-multi-store",
+            OptimizerPass::EliminateDeadConstant                => "eliminate-dead-const",
+            OptimizerPass::EliminateDeadInserts                 => "eliminate-dead-inserts",
+            OptimizerPass::EliminateDeadVariables               => "eliminate-dead-variables",
+            OptimizerPass::EliminateDeadMembers                 => "eliminate-dead-members",
+            OptimizerPass::FoldSpecConstantOpComposite          => "fold-spec-const-op-composite",
+            OptimizerPass::LoopUnswitch                         => "loop-unswitch",
+            OptimizerPass::ScalarReplacement { .. }             => "scalar-replacement",
+            OptimizerPass::StrengthReduction                    => "strength-reduction",
+            OptimizerPass::UnifyConstant                        => "unify-const",
+            OptimizerPass::FlattenDecorations                   => "flatten-decorations",
+            OptimizerPass::CompactIds                           => "compact-ids",
+            OptimizerPass::CfgCleanup                           => "cfg-cleanup",
+            OptimizerPass::LocalRedundancyElimination           => "local-redundancy-elimination",
+            OptimizerPass::LoopInvariantCodeMotion              => "loop-invariant-code-motion",
+            OptimizerPass::ReduceLoadSize { .. }                => "reduce-load-size",
+            OptimizerPass::RedundancyElimination                => "redundancy-elimination",
+            OptimizerPass::PrivateToLocal                       => "private-to-local",
+            OptimizerPass::RemoveDuplicates                     => "remove-duplicates",
+            OptimizerPass::Workaround1209                       => "workaround-1209",
+            OptimizerPass::ReplaceInvalidOpcode                 => "replace-invalid-opcode",
+            OptimizerPass::InstBindlessCheck                    => "inst-bindless-check",
+            OptimizerPass::InstDescIdxCheck                     => "inst-desc-idx-check",
+            OptimizerPass::InstBuffOobCheck                     => "inst-buff-oob-check",
+            OptimizerPass::InstBuffAddrCheck                    => "inst-buff-addr-check",
+            OptimizerPass::ConvertRelaxedToHalf                 => "convert-relaxed-to-half",
+            OptimizerPass::RelaxFloatOps                        => "relax-float-ops",
+            OptimizerPass::InstDebugPrintf                      => "inst-debug-printf",
+            OptimizerPass::SimplifyInstructions                 => "simplify-instructions",
+            OptimizerPass::SsaRewrite                           => "ssa-rewrite",
+            OptimizerPass::CopyPropagateArrays                  => "copy-propagate-arrays",
+            OptimizerPass::LoopFission { .. }                   => "loop-fission",
+            OptimizerPass::LoopFusion { .. }                    => "loop-fusion",
+            OptimizerPass::LoopUnroll                           => "loop-unroll",
+            OptimizerPass::LoopUnrollPartial { .. }             => "loop-unroll-partial",
+            OptimizerPass::UpgradeMemoryModel                   => "upgrade-memory-model",
+            OptimizerPass::VectorDce                            => "vector-dce",
+            OptimizerPass::LoopPeeling                          => "loop-peeling",
+            OptimizerPass::LoopPeelingThreshold(_)              => "loop-peeling-threshold",
+            OptimizerPass::Ccp                                  => "ccp",
+            OptimizerPass::CodeSink                             => "code-sink",
+            OptimizerPass::FixStorageClass                      => "fix-storage-class",
+            OptimizerPass::RemoveUnusedInterfaceVariables       => "remove-unused-interface-variables",
+            OptimizerPass::GraphicsRobustAccess                 => "graphics-robust-access",
+            OptimizerPass::WrapOpKill                           => "wrap-opkill",
+            OptimizerPass::AmdExtToKhr                          => "amd-ext-to-khr",
+            OptimizerPass::InterpolateFixup                     => "interpolate-fixup",
+            OptimizerPass::RemoveDontInline                     => "remove-dont-inline",
+            OptimizerPass::EliminateDeadInputComponents         => "eliminate-dead-input-components",
+            OptimizerPass::FixFuncCallParam                     => "fix-func-call-param",
+            OptimizerPass::ConvertToSampledImage(_)             => "convert-to-sampled-image"
+        }
+    }
+
+    /// Build a pass without arguments from it's name
+    fn from_name(name: &str) -> Option<Self> {
+        let pass = match name {
+            "O"                                         => OptimizerPass::Performance,
+            "Os"                                        => OptimizerPass::Size,
+            "legalize-hlsl"                             => OptimizerPass::LegalizeHlsl,
+            "strip-debug"                               => OptimizerPass::StripDebugInfo,
+            "strip-reflect"                             => OptimizerPass::StripReflectInfo,
+            "strip-nonsemantic"                         => OptimizerPass::StripNonSemanticInfo,
+            "if-conversion"                             => OptimizerPass::IfConversion,
+            "freeze-spec-const"                         => OptimizerPass::FreezeSpecConstantValue,
+            "inline-entry-points-exhaustive"            => OptimizerPass::InlineEntryPointsExhaustive,
+            "inline-entry-points-opaque"                => OptimizerPass::InlineEntryPointsOpaque,
+            "combine-access-chains"                     => OptimizerPass::CombineAccessChains,
+            "convert-local-access-chains"               => OptimizerPass::ConvertLocalAccessChains,
+            "replace-desc-array-access-using-var-index" => OptimizerPass::ReplaceDescArrayAccessUsingVarIndex,
+            "spread-volatile-semantics"                 => OptimizerPass::SpreadVolatileSemantics,
+            "descriptor-scalar-replacement"             => OptimizerPass::DescriptorScalarReplacement,
+            "eliminate-dead-code-aggressive"            => OptimizerPass::EliminateDeadCode,
+            "eliminate-insert-extract"                  => OptimizerPass::EliminateInsertExtract,
+            "eliminate-local-single-block"              => OptimizerPass::EliminateLocalSingleBlock,
+            "eliminate-local-single-store"              => OptimizerPass::EliminateLocalSingleStore,
+            "merge-blocks"                              => OptimizerPass::MergeBlocks,
+            "merge-return"                              => OptimizerPass::MergeReturn,
+            "eliminate-dead-branches"                   => OptimizerPass::EliminateDeadBranches,
+            "eliminate-dead-functions"                  => OptimizerPass::EliminateDeadFunctions,
+            "eliminate-local-multi-store"               => OptimizerPass::EliminateLocalMultiStore,
+            "eliminate-dead-const"                      => OptimizerPass::EliminateDeadConstant,
+            "eliminate-dead-inserts"                    => OptimizerPass::EliminateDeadInserts,
+            "eliminate-dead-variables"                  => OptimizerPass::EliminateDeadVariables,
+            "eliminate-dead-members"                    => OptimizerPass::EliminateDeadMembers,
+            "fold-spec-const-op-composite"              => OptimizerPass::FoldSpecConstantOpComposite,
+            "loop-unswitch"                             => OptimizerPass::LoopUnswitch,
+            "scalar-replacement"                        => OptimizerPass::ScalarReplacement { limit: None },
+            "strength-reduction"                        => OptimizerPass::StrengthReduction,
+            "unify-const"                               => OptimizerPass::UnifyConstant,
+            "flatten-decorations"                       => OptimizerPass::FlattenDecorations,
+            "compact-ids"                               => OptimizerPass::CompactIds,
+            "cfg-cleanup"                               => OptimizerPass::CfgCleanup,
+            "local-redundancy-elimination"              => OptimizerPass::LocalRedundancyElimination,
+            "loop-invariant-code-motion"                => OptimizerPass::LoopInvariantCodeMotion,
+            "reduce-load-size"                          => OptimizerPass::ReduceLoadSize { threshold: None },
+            "redundancy-elimination"                    => OptimizerPass::RedundancyElimination,
+            "private-to-local"                          => OptimizerPass::PrivateToLocal,
+            "remove-duplicates"                         => OptimizerPass::RemoveDuplicates,
+            "workaround-1209"                           => OptimizerPass::Workaround1209,
+            "replace-invalid-opcode"                    => OptimizerPass::ReplaceInvalidOpcode,
+            "inst-bindless-check"                       => OptimizerPass::InstBindlessCheck,
+            "inst-desc-idx-check"                       => OptimizerPass::InstDescIdxCheck,
+            "inst-buff-oob-check"                       => OptimizerPass::InstBuffOobCheck,
+            "inst-buff-addr-check"                      => OptimizerPass::InstBuffAddrCheck,
+            "convert-relaxed-to-half"                   => OptimizerPass::ConvertRelaxedToHalf,
+            "relax-float-ops"                           => OptimizerPass::RelaxFloatOps,
+            "inst-debug-printf"                         => OptimizerPass::InstDebugPrintf,
+            "simplify-instructions"                     => OptimizerPass::SimplifyInstructions,
+            "ssa-rewrite"                               => OptimizerPass::SsaRewrite,
+            "copy-propagate-arrays"                     => OptimizerPass::CopyPropagateArrays,
+            "loop-unroll"                               => OptimizerPass::LoopUnroll,
+            "upgrade-memory-model"                      => OptimizerPass::UpgradeMemoryModel,
+            "vector-dce"                                => OptimizerPass::VectorDce,
+            "loop-peeling"                              => OptimizerPass::LoopPeeling,
+            "ccp"                                       => OptimizerPass::Ccp,
+            "code-sink"                                 => OptimizerPass::CodeSink,
+            "fix-storage-class"                         => OptimizerPass::FixStorageClass,
+            "remove-unused-interface-variables"         => OptimizerPass::RemoveUnusedInterfaceVariables,
+            "graphics-robust-access"                    => OptimizerPass::GraphicsRobustAccess,
+            "wrap-opkill"                               => OptimizerPass::WrapOpKill,
+            "amd-ext-to-khr"                            => OptimizerPass::AmdExtToKhr,
+            "interpolate-fixup"                         => OptimizerPass::InterpolateFixup,
+            "remove-dont-inline"                        => OptimizerPass::RemoveDontInline,
+            "eliminate-dead-input-components"           => OptimizerPass::EliminateDeadInputComponents,
+            "fix-func-call-param"                       => OptimizerPass::FixFuncCallParam,
+            _                                           => return None
+        };
+
+        Some(pass)
+    }
+
+    /// Build a pass taking arguments from it's name and arguments
+    fn from_name_and_args(name: &str, args: &str) -> Option<Self> {
+        let pass = match name {
+            "set-spec-const-default-value"  => OptimizerPass::SetSpecConstantDefaultValue(parse_pairs(args, |x| Some(x.to_owned()).filter(|x| !x.contains('\0')))?),
+            "scalar-replacement"            => OptimizerPass::ScalarReplacement { limit: Some(args.parse().ok()?) },
+            "reduce-load-size"              => OptimizerPass::ReduceLoadSize { threshold: Some(args.parse().ok().filter(|x: &f64| x.is_finite() && *x >= 0.0)?) },
+            "loop-fission"                  => OptimizerPass::LoopFission { register_threshold: parse_positive(args)? },
+            "loop-fusion"                   => OptimizerPass::LoopFusion { max_registers: parse_positive(args)? },
+            "loop-unroll-partial"           => OptimizerPass::LoopUnrollPartial { factor: parse_positive(args)? },
+            "loop-peeling-threshold"        => OptimizerPass::LoopPeelingThreshold(parse_positive(args)?),
+            "convert-to-sampled-image"      => OptimizerPass::ConvertToSampledImage(parse_pairs(args, |x| x.parse().ok())?),
+            _                               => return None
+        };
+
+        Some(pass)
+    }
+}
+
+// Formats the pass as the flag registering it
+impl Display for OptimizerPass {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            OptimizerPass::Performance | OptimizerPass::Size => return write!(f, "-{}", self.name()),
+            _                                                => write!(f, "--{}", self.name())?
+        }
+
+        match self {
+            OptimizerPass::SetSpecConstantDefaultValue(values)          => write_pairs(f, values),
+            OptimizerPass::ScalarReplacement { limit: Some(limit) }     => write!(f, "={}", limit),
+            OptimizerPass::ReduceLoadSize { threshold: Some(threshold) } => write!(f, "={}", threshold),
+            OptimizerPass::LoopFission { register_threshold }           => write!(f, "={}", register_threshold),
+            OptimizerPass::LoopFusion { max_registers }                 => write!(f, "={}", max_registers),
+            OptimizerPass::LoopUnrollPartial { factor }                 => write!(f, "={}", factor),
+            OptimizerPass::LoopPeelingThreshold(threshold)              => write!(f, "={}", threshold),
+            OptimizerPass::ConvertToSampledImage(bindings)              => write_pairs(f, bindings),
+            _                                                           => Ok(())
+        }
+    }
+}
+
+// Parses flags of the form `--pass_name[=pass_args]`, `-O` and `-Os`
+impl FromStr for OptimizerPass {
+    type Err = OptimizerError;
+
+    fn from_str(flag: &str) -> Result<Self, Self::Err> {
+        let invalid = || OptimizerError::InvalidFlag(flag.to_owned());
+
+        let pass = match flag {
+            "-O"    => return Ok(OptimizerPass::Performance),
+            "-Os"   => return Ok(OptimizerPass::Size),
+            _       => flag.strip_prefix("--").ok_or_else(invalid)?
+        };
+
+        let parsed = match pass.split_once('=') {
+            Some((name, args))  => OptimizerPass::from_name_and_args(name, args),
+            None                => OptimizerPass::from_name(pass)
+        };
+
+        parsed.ok_or_else(invalid)
+    }
+}
+
+/// Write a list of pairs as the argument of a flag, e.g. `=1:2 3:4`
+fn write_pairs<T: Display>(f: &mut fmt::Formatter, pairs: &[(u32, T)]) -> fmt::Result {
+    for (i, (key, value)) in pairs.iter().enumerate() {
+        let separator = if i == 0 { '=' } else { ' ' };
+        write!(f, "{}{}:{}", separator, key, value)?;
+    }
+
+    Ok(())
+}
+
+/// Parse a whitespace separated list of `<u32>:<value>` pairs. At least one pair
+/// is required.
+fn parse_pairs<T, F: Fn(&str) -> Option<T>>(args: &str, parse_value: F) -> Option<Vec<(u32, T)>> {
+    let pairs = args.split_whitespace()
+        .map(|pair| {
+            let (key, value) = pair.split_once(':')?;
+            Some((key.parse().ok()?, parse_value(value).filter(|_| !value.is_empty())?))
+        })
+        .collect::<Option<Vec<_>>>()?;
+
+    if pairs.is_empty() { None } else { Some(pairs) }
+}
+
+/// Parse an integer greater than 0
+fn parse_positive(args: &str) -> Option<u32> {
+    args.parse()
+        .ok()
+        .filter(|x| *x > 0)
+}
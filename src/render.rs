@@ -0,0 +1,177 @@
+use std::fmt::Write;
+
+use crate::error::DiagnosticInfo;
+use crate::message::{Message, MessageLevel, Position};
+
+const RESET: &str      = "\x1b[0m";
+const BOLD: &str       = "\x1b[1m";
+const BOLD_BLUE: &str  = "\x1b[1;34m";
+
+/// Renders diagnostics rustc style, with the offending source line underlined and
+/// the surrounding lines for context.
+///
+/// ```text
+/// error: Invalid Opcode name 'OpNotAnInstruction'
+///  --> 2:6
+///   |
+/// 1 | OpCapability Shader
+/// 2 | %1 = OpNotAnInstruction
+///   |      ^^^^^^^^^^^^^^^^^^
+/// ```
+#[derive(Clone, Copy, Debug)]
+pub struct DiagnosticRenderer {
+    color: bool,
+    context_lines: usize
+}
+
+impl DiagnosticRenderer {
+    /// Create a renderer for plain output, showing two lines of context around
+    /// the offending line
+    pub fn new() -> Self {
+        Self {
+            color: false,
+            context_lines: 2
+        }
+    }
+
+    /// Color the output with ANSI escape codes, for printing to a terminal
+    pub fn color(self, color: bool) -> Self {
+        Self { color, ..self }
+    }
+
+    /// Set the number of lines shown before and after the offending line
+    pub fn context_lines(self, lines: usize) -> Self {
+        Self { context_lines: lines, ..self }
+    }
+
+    /// Render a diagnostic returned from an operation on `source`. Diagnostics
+    /// for binaries point at the index spirv-tools reports, as there is no source
+    /// to show.
+    pub fn render(&self, source: &str, diagnostic: &DiagnosticInfo) -> String {
+        let source = if diagnostic.is_text_source() { Some(source) } else { None };
+        self.render_at(MessageLevel::Error, diagnostic.message(), diagnostic.position(), source)
+    }
+
+    /// Render a message reported to a message consumer while assembling `source`
+    pub fn render_text_message(&self, source: &str, message: &Message) -> String {
+        self.render_at(message.level(), message.message(), message.position(), Some(source))
+    }
+
+    /// Render a message reported to a message consumer while processing a binary,
+    /// such as those from the validator. They point at the index spirv-tools
+    /// reports, as there is no source to show.
+    pub fn render_binary_message(&self, message: &Message) -> String {
+        self.render_at(message.level(), message.message(), message.position(), None)
+    }
+
+    /// Render a message at a position, with a snippet if the source is available
+    fn render_at(&self, level: MessageLevel, message: &str, position: Position, source: Option<&str>) -> String {
+        let mut out = String::new();
+
+        let _ = writeln!(
+            out,
+            "{}{}{}: {}{}{}",
+            self.style(level_style(level)),
+            level,
+            self.style(RESET),
+            self.style(BOLD),
+            message,
+            self.style(RESET)
+        );
+
+        let lines: Vec<&str> = source
+            .map(|x| x.lines().collect())
+            .unwrap_or_default();
+
+        // Without a matching source line there is nothing to underline
+        let line = match lines.get(position.line) {
+            Some(line) => *line,
+            None       => {
+                let _ = match source {
+                    Some(_) => writeln!(out, " {}-->{} {}:{}", self.style(BOLD_BLUE), self.style(RESET), position.line + 1, position.column + 1),
+                    None    => writeln!(out, " {}-->{} index {}", self.style(BOLD_BLUE), self.style(RESET), position.index)
+                };
+
+                return out;
+            }
+        };
+
+        let first = position.line.saturating_sub(self.context_lines);
+        let last = position.line.saturating_add(self.context_lines).min(lines.len() - 1);
+        let gutter = (last + 1).to_string().len();
+        let blank = " ".repeat(gutter);
+
+        let _ = writeln!(out, "{}{}-->{} {}:{}", blank, self.style(BOLD_BLUE), self.style(RESET), position.line + 1, position.column + 1);
+        let _ = writeln!(out, "{} {}|{}", blank, self.style(BOLD_BLUE), self.style(RESET));
+
+        for (number, text) in lines.iter().enumerate().take(last + 1).skip(first) {
+            let _ = writeln!(out, "{}{:>width$} |{} {}", self.style(BOLD_BLUE), number + 1, self.style(RESET), text, width = gutter);
+
+            if number == position.line {
+                let _ = writeln!(
+                    out,
+                    "{} {}|{} {}{}{}{}",
+                    blank,
+                    self.style(BOLD_BLUE),
+                    self.style(RESET),
+                    underline_padding(line, position.column),
+                    self.style(level_style(level)),
+                    "^".repeat(underline_len(line, position.column)),
+                    self.style(RESET)
+                );
+            }
+        }
+
+        out
+    }
+
+    /// An escape code, or nothing if the output is plain
+    fn style(&self, code: &'static str) -> &'static str {
+        if self.color { code } else { "" }
+    }
+}
+
+impl Default for DiagnosticRenderer {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// The escape code used for the severity of a message
+fn level_style(level: MessageLevel) -> &'static str {
+    match level {
+        MessageLevel::Fatal | MessageLevel::InternalError | MessageLevel::Error => "\x1b[1;31m",
+        MessageLevel::Warning                                                   => "\x1b[1;33m",
+        MessageLevel::Info | MessageLevel::Debug                                => "\x1b[1;36m"
+    }
+}
+
+/// Whitespace lining the underline up with the byte offset `column`, keeping
+/// tabs so it lines up regardless of the tab width
+fn underline_padding(line: &str, column: usize) -> String {
+    split_at_column(line, column).0
+        .chars()
+        .map(|x| if x == '\t' { '\t' } else { ' ' })
+        .collect()
+}
+
+/// Underline the token starting at the byte offset `column`, or a single
+/// character if there is none
+fn underline_len(line: &str, column: usize) -> usize {
+    split_at_column(line, column).1
+        .chars()
+        .take_while(|x| !x.is_whitespace())
+        .count()
+        .max(1)
+}
+
+/// Split a line at the byte offset spirv-tools reports as the column, moving back
+/// to the start of the character it falls in
+fn split_at_column(line: &str, column: usize) -> (&str, &str) {
+    let mut at = column.min(line.len());
+    while !line.is_char_boundary(at) {
+        at -= 1;
+    }
+
+    line.split_at(at)
+}
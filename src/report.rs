@@ -0,0 +1,158 @@
+use std::panic;
+use std::thread;
+
+use crate::{Context, TargetEnv, ValidatorOptions};
+use crate::error::ValidateError;
+
+/// The result of validating a module for one target environment
+#[derive(Clone, Debug)]
+pub struct TargetValidation {
+    /// The environment the module was validated for
+    pub env: TargetEnv,
+    /// The outcome, with the diagnostic of the first error if the module is not
+    /// valid for the environment
+    pub result: Result<(), ValidateError>
+}
+
+impl TargetValidation {
+    /// Whether the module is valid for the environment
+    pub fn is_valid(&self) -> bool {
+        self.result.is_ok()
+    }
+}
+
+/// The results of validating one module for several target environments, in the
+/// order the environments were given
+#[derive(Clone, Debug)]
+pub struct ValidationReport {
+    targets: Vec<TargetValidation>
+}
+
+impl ValidationReport {
+    /// The result for every environment
+    pub fn targets(&self) -> &[TargetValidation] {
+        &self.targets
+    }
+
+    /// The result for an environment, or `None` if it was not validated for
+    pub fn result(&self, env: TargetEnv) -> Option<&Result<(), ValidateError>> {
+        self.targets.iter()
+            .find(|x| x.env == env)
+            .map(|x| &x.result)
+    }
+
+    /// Whether the module is valid for every environment
+    pub fn is_valid(&self) -> bool {
+        self.targets.iter().all(TargetValidation::is_valid)
+    }
+
+    /// The environments the module is valid for
+    pub fn valid_targets(&self) -> Vec<TargetEnv> {
+        self.targets.iter()
+            .filter(|x| x.is_valid())
+            .map(|x| x.env)
+            .collect()
+    }
+
+    /// The environments the module is not valid for
+    pub fn failed_targets(&self) -> Vec<TargetEnv> {
+        self.targets.iter()
+            .filter(|x| !x.is_valid())
+            .map(|x| x.env)
+            .collect()
+    }
+
+    /// The smallest set of environments that covers every valid one.
+    ///
+    /// A module for an older version of an API also runs on the newer versions,
+    /// so this is the oldest valid environment of each API, keeping the embedded
+    /// OpenCL profiles apart from the full ones.
+    pub fn minimal_targets(&self) -> Vec<TargetEnv> {
+        let valid = self.valid_targets();
+
+        let mut minimal = Vec::new();
+        for &env in &valid {
+            let covered = valid.iter()
+                .any(|&other| other != env && covers(other, env));
+
+            if !covered && !minimal.contains(&env) {
+                minimal.push(env);
+            }
+        }
+
+        minimal
+    }
+}
+
+/// Validate a module for each of the target environments in parallel.
+///
+/// Every environment gets its own context with diagnostics included, so the
+/// result for a failed environment carries the first error spirv-tools found.
+pub fn validate_for_targets(binary: &[u32], targets: &[TargetEnv], options: &ValidatorOptions) -> ValidationReport {
+    let targets = thread::scope(|scope| {
+        let workers: Vec<_> = targets.iter()
+            .map(|&env| scope.spawn(move || {
+                let ctx = Context::new(env)
+                    .with_diagnostics();
+
+                TargetValidation { env, result: ctx.validate_with_options(binary, options) }
+            }))
+            .collect();
+
+        workers.into_iter()
+            .map(|x| x.join().unwrap_or_else(|e| panic::resume_unwind(e)))
+            .collect()
+    });
+
+    ValidationReport { targets }
+}
+
+/// Whether a module valid for `older` also runs wherever `newer` is supported
+fn covers(older: TargetEnv, newer: TargetEnv) -> bool {
+    older.family() == newer.family()
+        && is_embedded(older) == is_embedded(newer)
+        && (api_version(older), older.max_spirv_version()) <= (api_version(newer), newer.max_spirv_version())
+}
+
+/// Whether the environment is one of the OpenCL embedded profiles
+fn is_embedded(env: TargetEnv) -> bool {
+    matches!(env,
+        TargetEnv::OpenCLEmbedded1_2
+        | TargetEnv::OpenCLEmbedded2_0
+        | TargetEnv::OpenCLEmbedded2_1
+        | TargetEnv::OpenCLEmbedded2_2
+    )
+}
+
+/// The version of the API an environment targets, as major and minor. The
+/// universal environments use their SPIR-V version.
+fn api_version(env: TargetEnv) -> (u8, u8) {
+    match env {
+        TargetEnv::Vulkan1_0             => (1, 0),
+        TargetEnv::Vulkan1_1             => (1, 1),
+        TargetEnv::Vulkan1_1Spirv1_4     => (1, 1),
+        TargetEnv::Vulkan1_2             => (1, 2),
+        TargetEnv::Vulkan1_3             => (1, 3),
+        TargetEnv::OpenCL1_2             => (1, 2),
+        TargetEnv::OpenCLEmbedded1_2     => (1, 2),
+        TargetEnv::OpenCL2_0             => (2, 0),
+        TargetEnv::OpenCLEmbedded2_0     => (2, 0),
+        TargetEnv::OpenCL2_1             => (2, 1),
+        TargetEnv::OpenCLEmbedded2_1     => (2, 1),
+        TargetEnv::OpenCL2_2             => (2, 2),
+        TargetEnv::OpenCLEmbedded2_2     => (2, 2),
+        TargetEnv::OpenGL4_0             => (4, 0),
+        TargetEnv::OpenGL4_1             => (4, 1),
+        TargetEnv::OpenGL4_2             => (4, 2),
+        TargetEnv::OpenGL4_3             => (4, 3),
+        TargetEnv::OpenGl4_5             => (4, 5),
+        TargetEnv::WebGPU0               => (0, 0),
+        TargetEnv::Universal1_0
+        | TargetEnv::Universal1_1
+        | TargetEnv::Universal1_2
+        | TargetEnv::Universal1_3
+        | TargetEnv::Universal1_4
+        | TargetEnv::Universal1_5
+        | TargetEnv::Universal1_6        => env.max_spirv_version()
+    }
+}
@@ -0,0 +1,117 @@
+use std::ffi::CStr;
+use std::fmt::{self, Display};
+
+use crate::raw::*;
+
+/// The commit of the spirv-tools sources the crate was built from.
+///
+/// The commit is read from git when the crate is built, so this is `None` unless
+/// the sources are the root of a git checkout, such as the initialized submodule
+/// of a clone of this repository. It is always `None` for the crate as published
+/// on crates.io, which ships the sources without their git history, and when an
+/// installed library was linked. `Version::commit` reports the commit the linked
+/// library was built from, if it knows it.
+pub const VENDORED_COMMIT: Option<&str> = option_env!("SPIRV_TOOLS_COMMIT");
+
+/// The version of the linked spirv-tools library
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct Version {
+    year: u16,
+    number: u16,
+    dev: bool,
+    string: &'static str,
+    details: &'static str
+}
+
+impl Version {
+    /// The year of the release, e.g. `2022` for `v2022.4`. 0 if the version
+    /// string is not in the usual format.
+    pub fn year(&self) -> u16 {
+        self.year
+    }
+
+    /// The release number within the year, e.g. `4` for `v2022.4`. 0 if the
+    /// version string is not in the usual format.
+    pub fn number(&self) -> u16 {
+        self.number
+    }
+
+    /// Whether the library was built from the development branch leading up to
+    /// the release, rather than the release itself
+    pub fn is_dev(&self) -> bool {
+        self.dev
+    }
+
+    /// The version string reported by the library, e.g. `v2022.4-dev`
+    pub fn as_str(&self) -> &'static str {
+        self.string
+    }
+
+    /// The project name, version and commit description reported by the library,
+    /// e.g. `SPIRV-Tools v2022.4 v2022.3-12-ge246c212`
+    pub fn details(&self) -> &'static str {
+        self.details
+    }
+
+    /// The abbreviated commit hash from the details, if the library was built
+    /// from a git checkout
+    pub fn commit(&self) -> Option<&'static str> {
+        self.details.split_whitespace()
+            .last()
+            .and_then(|x| x.rsplit_once("-g"))
+            .map(|x| x.1)
+            .filter(|x| !x.is_empty() && x.chars().all(|c| c.is_ascii_hexdigit()))
+    }
+
+    /// Split a version string of the form `v<year>.<number>[-dev]`
+    fn parse(string: &'static str, details: &'static str) -> Self {
+        let trimmed = string.trim().trim_start_matches('v');
+        let (release, dev) = match trimmed.strip_suffix("-dev") {
+            Some(release) => (release, true),
+            None          => (trimmed, false)
+        };
+
+        let mut parts = release.splitn(2, '.')
+            .map(|x| x.parse::<u16>().ok());
+
+        let (year, number) = match (parts.next().flatten(), parts.next().flatten()) {
+            (Some(year), Some(number)) => (year, number),
+            _                          => (0, 0)
+        };
+
+        Self {
+            year,
+            number,
+            dev,
+            string,
+            details
+        }
+    }
+}
+
+impl Display for Version {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str(self.string)
+    }
+}
+
+/// The version of the linked spirv-tools library, for example to invalidate
+/// cached results when the library changes
+pub fn version() -> Version {
+    let (string, details) = unsafe {
+        (static_str(spvSoftwareVersionString()), static_str(spvSoftwareVersionDetailsString()))
+    };
+
+    Version::parse(string, details)
+}
+
+/// Borrow a string owned by the library for the rest of the process
+unsafe fn static_str(ptr: *const libc::c_char) -> &'static str {
+    if ptr.is_null() {
+        return "";
+    }
+
+    CStr::from_ptr(ptr)
+        .to_str()
+        .unwrap_or("")
+}
@@ -2,7 +2,7 @@ extern crate spirv_tools_rs;
 
 use spirv_tools_rs::*;
 
-const ASM_SRC: &'static str = r#"
+const ASM_SRC: &str = r#"
     ; Magic:     0x07230203 (SPIR-V)
     ; Version:   0x00010000 (Version: 1.0.0)
     ; Generator: 0x00080001 (Khronos Glslang Reference Front End; 1)
@@ -134,7 +134,7 @@ fn assemble() {
     let assembled = ctx.assemble(ASM_SRC);
 
     assert!(assembled.is_ok(), "Assembly failed with '{:?}'", assembled);
-    assert!(assembled.unwrap().len() > 0);
+    assert!(!assembled.unwrap().is_empty());
 }
 
 #[test]
@@ -145,7 +145,7 @@ fn disassemble() {
     let disassembled = ctx.disassemble(&assembled);
 
     assert!(disassembled.is_ok(), "Disassembly failed with '{:?}'", disassembled);
-    assert!(disassembled.unwrap().len() > 0);
+    assert!(!disassembled.unwrap().is_empty());
 
 }
 
@@ -158,7 +158,7 @@ fn validate() {
     let validated = ctx.validate(&assembled);
 
     assert!(validated.is_ok(), "Validation failed with '{:?}'", validated);
-    assert!(assembled.len() > 0);
+    assert!(!assembled.is_empty());
 }
 
 #[test]
@@ -174,5 +174,1101 @@ fn optimize() {
     let validated = ctx.validate(&optimized);
 
     assert!(validated.is_ok(), "Optimization failed with '{:?}'", validated);
-    assert!(optimized.len() > 0);
-}
\ No newline at end of file
+    assert!(!optimized.is_empty());
+}
+
+#[test]
+fn shared_context() {
+    let ctx = std::sync::Arc::new(Context::new(TargetEnv::OpenGl4_5));
+
+    let workers: Vec<_> = (0..4)
+        .map(|_| {
+            let ctx = ctx.clone();
+            std::thread::spawn(move || {
+                let assembled = ctx.assemble(ASM_SRC)
+                    .unwrap();
+
+                ctx.validate(&assembled)
+            })
+        })
+        .collect();
+
+    for worker in workers {
+        let validated = worker.join()
+            .unwrap();
+
+        assert!(validated.is_ok(), "Validation failed with '{:?}'", validated);
+    }
+}
+
+#[test]
+fn context_message_consumer() {
+    let messages = std::sync::Arc::new(std::sync::Mutex::new(Vec::new()));
+
+    let mut ctx = Context::new(TargetEnv::OpenGl4_5);
+    let consumed = messages.clone();
+    ctx.set_message_consumer(move |x| consumed.lock().unwrap().push(x));
+
+    let assembled = ctx.assemble("OpCapability Shader\n%1 = OpNotAnInstruction");
+    assert!(assembled.is_err());
+
+    let messages = messages.lock()
+        .unwrap();
+
+    assert!(!messages.is_empty());
+    assert_eq!(messages[0].level(), MessageLevel::Error);
+    assert_eq!(messages[0].position().line, 1);
+}
+
+#[test]
+fn reentrant_message_consumer() {
+    let shared = std::sync::Arc::new(std::sync::OnceLock::<std::sync::Weak<Context>>::new());
+    let calls = std::sync::Arc::new(std::sync::atomic::AtomicUsize::new(0));
+
+    let mut ctx = Context::new(TargetEnv::OpenGl4_5);
+    let (inner, counted) = (shared.clone(), calls.clone());
+    ctx.set_message_consumer(move |_| {
+        counted.fetch_add(1, std::sync::atomic::Ordering::SeqCst);
+
+        // Reports a message of its own while this one is being consumed
+        if let Some(ctx) = inner.get().and_then(std::sync::Weak::upgrade) {
+            assert!(ctx.validate(&[0x0723_0203]).is_err());
+        }
+    });
+
+    let ctx = std::sync::Arc::new(ctx);
+    shared.set(std::sync::Arc::downgrade(&ctx))
+        .unwrap();
+
+    assert!(ctx.validate(&[0x0723_0203]).is_err());
+    assert_eq!(calls.load(std::sync::atomic::Ordering::SeqCst), 1);
+}
+
+#[test]
+fn optimizer_message_consumer() {
+    let messages = std::sync::Arc::new(std::sync::Mutex::new(Vec::new()));
+
+    let consumed = messages.clone();
+    let mut opt = Optimizer::new(TargetEnv::OpenGl4_5)
+        .register_performance_passes();
+    opt.set_message_consumer(move |x| consumed.lock().unwrap().push(x));
+
+    let optimized = opt.run(&[0xDEAD_BEEF, 0, 0, 0, 0]);
+    assert!(optimized.is_err());
+
+    let messages = messages.lock()
+        .unwrap();
+
+    assert!(messages.iter().any(|x| x.level().is_error()), "No errors in '{:?}'", messages);
+}
+
+#[test]
+fn message_consumer_panic() {
+    let mut ctx = Context::new(TargetEnv::OpenGl4_5);
+    ctx.set_message_consumer(|_| panic!("consumer panicked"));
+
+    let result = std::panic::catch_unwind(|| ctx.assemble("%1 = OpNotAnInstruction"));
+    assert!(result.is_err());
+
+    // The context stays usable after the panic
+    ctx.clear_message_consumer();
+    assert!(ctx.assemble(ASM_SRC).is_ok());
+}
+
+#[test]
+fn assemble_diagnostic() {
+    let ctx = Context::new(TargetEnv::OpenGl4_5)
+        .with_diagnostics();
+
+    let diag = match ctx.assemble("OpCapability Shader\n%1 = OpNotAnInstruction") {
+        Err(AssembleError::SpirvTools(_, Some(diag))) => diag,
+        x                                             => panic!("Expected a diagnostic, got '{:?}'", x)
+    };
+
+    assert!(diag.is_text_source());
+    assert_eq!(diag.line(), 1);
+    assert_eq!(diag.column(), 5);
+    assert!(diag.message().contains("OpNotAnInstruction"), "Unexpected message '{}'", diag.message());
+    assert_eq!(diag.to_string(), format!("Error: 1: 5: {}", diag.message()));
+}
+
+#[test]
+fn render_diagnostic() {
+    let source = "OpCapability Shader\n%1 = OpNotAnInstruction\nOpMemoryModel Logical GLSL450";
+    let ctx = Context::new(TargetEnv::OpenGl4_5)
+        .with_diagnostics();
+
+    let diag = match ctx.assemble(source) {
+        Err(AssembleError::SpirvTools(_, Some(diag))) => diag,
+        x                                             => panic!("Expected a diagnostic, got '{:?}'", x)
+    };
+
+    let rendered = DiagnosticRenderer::new()
+        .render(source, &diag);
+    let expected = format!(
+        "error: {}\n --> 2:6\n  |\n1 | OpCapability Shader\n2 | %1 = OpNotAnInstruction\n  |      ^^^^^^^^^^^^^^^^^^\n3 | OpMemoryModel Logical GLSL450\n",
+        diag.message()
+    );
+    assert_eq!(rendered, expected);
+
+    let colored = DiagnosticRenderer::new()
+        .color(true)
+        .render(source, &diag);
+    assert!(colored.contains("\x1b[1;31merror"));
+
+    let everything = DiagnosticRenderer::new()
+        .context_lines(usize::MAX)
+        .render(source, &diag);
+    assert!(everything.contains("1 | OpCapability Shader\n"));
+    assert!(everything.contains("3 | OpMemoryModel Logical GLSL450\n"));
+}
+
+#[test]
+fn render_binary_message() {
+    let messages = std::sync::Arc::new(std::sync::Mutex::new(Vec::new()));
+
+    let mut ctx = Context::new(TargetEnv::OpenGl4_5);
+    let consumed = messages.clone();
+    ctx.set_message_consumer(move |x| consumed.lock().unwrap().push(x));
+
+    let assembled = ctx.assemble(ASM_SRC)
+        .unwrap();
+    assert!(ctx.validate(&assembled[..assembled.len() - 1]).is_err());
+
+    let messages = messages.lock()
+        .unwrap();
+    let message = &messages[0];
+
+    let rendered = DiagnosticRenderer::new()
+        .render_binary_message(message);
+    assert_eq!(rendered, format!("error: {}\n --> index {}\n", message.message(), message.position().index));
+}
+
+#[test]
+fn render_text_message() {
+    let messages = std::sync::Arc::new(std::sync::Mutex::new(Vec::new()));
+
+    let mut ctx = Context::new(TargetEnv::OpenGl4_5);
+    let consumed = messages.clone();
+    ctx.set_message_consumer(move |x| consumed.lock().unwrap().push(x));
+
+    // An error at the very start of the source is still shown with its line
+    let source = "OpNotAnInstruction\nOpCapability Shader";
+    assert!(ctx.assemble(source).is_err());
+
+    let message = messages.lock().unwrap().remove(0);
+    let rendered = DiagnosticRenderer::new()
+        .render_text_message(source, &message);
+    let expected = format!(
+        "error: {}\n --> 1:1\n  |\n1 | OpNotAnInstruction\n  | ^^^^^^^^^^^^^^^^^^\n2 | OpCapability Shader\n",
+        message.message()
+    );
+    assert_eq!(rendered, expected);
+
+    // Columns are byte offsets, so the underline still starts at the token
+    // after a multibyte character
+    let source = "OpName %1 \"\u{e9}\" %2 = OpNotAnInstruction";
+    assert!(ctx.assemble(source).is_err());
+
+    let message = messages.lock().unwrap().remove(0);
+    let rendered = DiagnosticRenderer::new()
+        .render_text_message(source, &message);
+    assert_eq!(message.position().column, 20);
+    assert!(rendered.ends_with(&format!("1 | {}\n  | {}{}\n", source, " ".repeat(19), "^".repeat(18))), "{}", rendered);
+}
+
+#[test]
+fn error_source() {
+    fn assemble(source: &str) -> Result<SpvBinaryBuf, Box<dyn std::error::Error>> {
+        let ctx = Context::new(TargetEnv::OpenGl4_5)
+            .with_diagnostics();
+
+        Ok(ctx.assemble(source)?)
+    }
+
+    let err = assemble(&String::from("%1 = OpNotAnInstruction"))
+        .unwrap_err();
+
+    assert!(err.to_string().starts_with("Assembly failed: Error: 0: 5: "), "Unexpected error '{}'", err);
+
+    let diag = err.source()
+        .and_then(|x| x.downcast_ref::<DiagnosticInfo>())
+        .unwrap();
+    assert_eq!((diag.line(), diag.column()), (0, 5));
+
+    let err = Context::new(TargetEnv::OpenGl4_5)
+        .assemble("%1 = OpNotAnInstruction")
+        .unwrap_err();
+    assert_eq!(std::error::Error::source(&err).unwrap().to_string(), SpvError::InvalidLookup.to_string());
+
+    let err = assemble("OpCapability\0Shader")
+        .unwrap_err();
+
+    assert_eq!(err.to_string(), "Assembly failed: source contains a nul byte at offset 12");
+    assert!(err.source().is_none());
+}
+
+#[test]
+fn assemble_preserve_numeric_ids() {
+    let ctx = Context::new(TargetEnv::OpenGl4_5);
+    let options = AssembleOptions::none()
+        .preserve_numeric_ids();
+
+    let assembled = ctx.assemble_with_options(ASM_SRC, options)
+        .unwrap();
+    let disassembled = ctx.disassemble(&assembled)
+        .unwrap();
+
+    assert!(disassembled.contains("%57 = OpVariable"), "IDs were renumbered in '{}'", disassembled);
+
+    let reassembled = ctx.assemble_with_options(&disassembled, options)
+        .unwrap();
+
+    assert_eq!(assembled, reassembled);
+}
+
+#[test]
+fn parse() {
+    use std::ops::ControlFlow;
+
+    let ctx = Context::new(TargetEnv::OpenGl4_5);
+    let assembled = ctx.assemble_with_options(ASM_SRC, AssembleOptions::none().preserve_numeric_ids())
+        .unwrap();
+
+    let mut header = None;
+    let mut variables = Vec::new();
+
+    let parsed = ctx.parse(
+        &assembled,
+        |x| {
+            header = Some(x);
+            ControlFlow::Continue(())
+        },
+        |x| {
+            // OpVariable
+            if x.opcode() == 59 {
+                let operands: Vec<_> = x.operands().collect();
+
+                assert_eq!(operands.len(), 3);
+                assert_eq!(operands[0].operand_type(), raw::spv_operand_type_t::TYPE_ID);
+                assert_eq!(operands[1].operand_type(), raw::spv_operand_type_t::RESULT_ID);
+                assert_eq!(operands[1].words(), &[x.result_id().unwrap()]);
+
+                variables.push(x.result_id().unwrap());
+            }
+
+            ControlFlow::Continue(())
+        }
+    );
+
+    assert!(parsed.is_ok(), "Parsing failed with '{:?}'", parsed);
+
+    let header = header.unwrap();
+    assert_eq!(header.magic, 0x0723_0203);
+    assert_eq!(header.endianness, Endianness::Little);
+    assert_eq!(header.id_bound, 63);
+    assert!(variables.contains(&57), "Missing variable in '{:?}'", variables);
+}
+
+#[test]
+fn parse_early_return() {
+    use std::ops::ControlFlow;
+
+    let ctx = Context::new(TargetEnv::OpenGl4_5);
+    let assembled = ctx.assemble(ASM_SRC)
+        .unwrap();
+
+    let mut count = 0;
+    let parsed = ctx.parse(&assembled, |_| ControlFlow::Continue(()), |_| {
+        count += 1;
+        if count == 3 { ControlFlow::Break(()) } else { ControlFlow::Continue(()) }
+    });
+
+    assert!(parsed.is_ok(), "Parsing failed with '{:?}'", parsed);
+    assert_eq!(count, 3);
+
+    let result = std::panic::catch_unwind(|| {
+        ctx.parse(&assembled, |_| panic!("header panicked"), |_| ControlFlow::Continue(()))
+    });
+    assert!(result.is_err());
+}
+
+#[test]
+fn module_header() {
+    let ctx = Context::new(TargetEnv::OpenGl4_5);
+    let assembled = ctx.assemble_with_options(ASM_SRC, AssembleOptions::none().preserve_numeric_ids())
+        .unwrap();
+
+    let header = ModuleHeader::parse(&assembled)
+        .unwrap();
+
+    assert_eq!(header.version, (1, 0));
+    assert_eq!(header.generator.id, 7);
+    assert_eq!(header.generator.tool(), Some("SPIR-V Tools Assembler"));
+    assert_eq!(header.bound, 63);
+    assert_eq!(header.schema, 0);
+
+    let swapped: Vec<u32> = assembled.iter()
+        .map(|x| x.swap_bytes())
+        .collect();
+    let swapped_header = ModuleHeader::parse(&swapped)
+        .unwrap();
+
+    assert_ne!(swapped_header.endianness, header.endianness);
+    assert_eq!(swapped_header.bound, header.bound);
+    assert_eq!(swapped_header.generator, header.generator);
+
+    assert_eq!(ModuleHeader::parse(&assembled[..3]), Err(HeaderError::Truncated(3)));
+    assert_eq!(ModuleHeader::parse(&[0xDEAD_BEEF, 0, 0, 0, 0]), Err(HeaderError::BadMagic(0xDEAD_BEEF)));
+}
+
+#[test]
+fn generator_names() {
+    assert_eq!(Generator::from_word(0x0008_000A).to_string(), "Khronos Glslang Reference Front End; 10");
+    assert_eq!(Generator::from_word(0x0001_0002).to_string(), "LunarG; 2");
+    assert_eq!(Generator::from_word(0xFFFF_0001).to_string(), "Unknown(65535); 1");
+}
+
+#[test]
+fn binary_from_bytes() {
+    use std::convert::TryFrom;
+
+    let ctx = Context::new(TargetEnv::OpenGl4_5);
+    let assembled = ctx.assemble(ASM_SRC)
+        .unwrap();
+
+    let big: Vec<u8> = assembled.iter()
+        .flat_map(|x| x.to_be_bytes().to_vec())
+        .collect();
+    let little: Vec<u8> = assembled.iter()
+        .flat_map(|x| x.to_le_bytes().to_vec())
+        .collect();
+
+    let from_big = SpirvBinary::try_from(&big[..])
+        .unwrap();
+    let from_little = SpirvBinary::try_from(&little[..])
+        .unwrap();
+
+    assert_eq!(from_big.endianness(), Endianness::Big);
+    assert_eq!(from_little.endianness(), Endianness::Little);
+    assert_eq!(from_big.words(), &assembled[..]);
+    assert_eq!(from_little.words(), &assembled[..]);
+    assert_eq!(from_big.header().endianness, Endianness::NATIVE);
+
+    // Unaligned buffers are accepted
+    let mut offset = vec![0];
+    offset.extend_from_slice(&big);
+    assert_eq!(SpirvBinary::from_bytes(&offset[1..]).unwrap(), from_big);
+
+    ctx.validate(&from_big)
+        .unwrap();
+
+    match SpirvBinary::from_bytes(&big[..big.len() - 1]) {
+        Err(BinaryError::InvalidLength(len)) => assert_eq!(len, big.len() - 1),
+        x                                    => panic!("Unexpected result: {:?}", x)
+    }
+
+    match SpirvBinary::from_bytes(&[0; 8]) {
+        Err(BinaryError::Header(HeaderError::BadMagic(0))) => {},
+        x                                                  => panic!("Unexpected result: {:?}", x)
+    }
+}
+
+#[test]
+fn binary_files() {
+    let ctx = Context::new(TargetEnv::OpenGl4_5);
+    let assembled = ctx.assemble(ASM_SRC)
+        .unwrap();
+
+    let bytes: Vec<u8> = assembled.iter()
+        .flat_map(|x| x.to_be_bytes().to_vec())
+        .collect();
+
+    let path = std::env::temp_dir().join(format!("spirv-tools-rs-{}.spv", std::process::id()));
+    std::fs::write(&path, &bytes)
+        .unwrap();
+
+    ctx.validate_file(&path)
+        .unwrap();
+    assert!(ctx.disassemble_file(&path).unwrap().contains("OpEntryPoint"));
+
+    let opt = Optimizer::new(TargetEnv::OpenGl4_5)
+        .register_performance_passes();
+    assert!(!opt.run_file(&path).unwrap().is_empty());
+
+    std::fs::remove_file(&path)
+        .unwrap();
+
+    match ctx.validate_file(&path) {
+        Err(ValidateError::Binary(BinaryError::Io(_))) => {},
+        x                                              => panic!("Unexpected result: {:?}", x)
+    }
+}
+
+#[test]
+fn owned_buffers() {
+    fn assert_send_sync<T: Send + Sync>(_: &T) {}
+
+    let ctx = Context::new(TargetEnv::OpenGl4_5);
+    let assembled = ctx.assemble(ASM_SRC)
+        .unwrap();
+    assert_send_sync(&assembled);
+
+    let disassembled = ctx.disassemble(&assembled)
+        .unwrap();
+    assert_send_sync(&disassembled);
+
+    let reassembled = ctx.assemble(&disassembled)
+        .unwrap();
+    assert_eq!(reassembled, assembled);
+
+    // Ids are renumbered by the assembler, so the entry point is only named %main
+    // when friendly names are used
+    let named = ctx.disassemble_with_options(&reassembled, DisassembleOptions::none().friendly_names())
+        .unwrap();
+    assert!(named.contains("OpEntryPoint Fragment %main \"main\""), "Unexpected disassembly '{}'", named);
+
+    let words = assembled.words().to_vec();
+    assert_eq!(assembled.into_vec(), words);
+    let text = disassembled.to_string();
+    assert_eq!(disassembled.into_string(), text);
+}
+
+#[test]
+fn spirv_1_6_environments() {
+    const TERMINATE_SRC: &str = r#"
+        OpCapability Shader
+        OpMemoryModel Logical GLSL450
+        OpEntryPoint Fragment %main "main"
+        OpExecutionMode %main OriginUpperLeft
+%void = OpTypeVoid
+  %fn = OpTypeFunction %void
+%main = OpFunction %void None %fn
+  %entry = OpLabel
+        OpTerminateInvocation
+        OpFunctionEnd
+    "#;
+
+    let ctx = Context::new(TargetEnv::Vulkan1_3);
+    let assembled = ctx.assemble(TERMINATE_SRC)
+        .unwrap();
+
+    assert_eq!(ModuleHeader::parse(&assembled).unwrap().version, (1, 6));
+    ctx.validate(&assembled)
+        .unwrap();
+    Context::new(TargetEnv::Universal1_6).validate(&assembled)
+        .unwrap();
+
+    assert!(Context::new(TargetEnv::Universal1_5).validate(&assembled).is_err());
+    assert!(Context::new(TargetEnv::Vulkan1_2).validate(&assembled).is_err());
+    assert!(Context::new(TargetEnv::Vulkan1_0).validate(&assembled).is_err());
+}
+
+#[test]
+fn target_env_names() {
+    let envs = [
+        TargetEnv::Universal1_0, TargetEnv::Universal1_1, TargetEnv::Universal1_2, TargetEnv::Universal1_3,
+        TargetEnv::Universal1_4, TargetEnv::Universal1_5, TargetEnv::Universal1_6,
+        TargetEnv::Vulkan1_0, TargetEnv::Vulkan1_1, TargetEnv::Vulkan1_1Spirv1_4, TargetEnv::Vulkan1_2,
+        TargetEnv::Vulkan1_3, TargetEnv::OpenCL1_2, TargetEnv::OpenCLEmbedded1_2, TargetEnv::OpenCL2_0,
+        TargetEnv::OpenCLEmbedded2_0, TargetEnv::OpenCL2_1, TargetEnv::OpenCLEmbedded2_1, TargetEnv::OpenCL2_2,
+        TargetEnv::OpenCLEmbedded2_2, TargetEnv::OpenGL4_0, TargetEnv::OpenGL4_1, TargetEnv::OpenGL4_2,
+        TargetEnv::OpenGL4_3, TargetEnv::OpenGl4_5, TargetEnv::WebGPU0
+    ];
+
+    for env in envs.iter() {
+        assert_eq!(env.name().parse::<TargetEnv>(), Ok(*env));
+        assert!(!env.to_string().is_empty());
+    }
+
+    assert_eq!("vulkan1.1".parse::<TargetEnv>(), Ok(TargetEnv::Vulkan1_1));
+    assert_eq!("not-an-env".parse::<TargetEnv>(), Err(ParseTargetEnvError::Unknown("not-an-env".to_owned())));
+    assert_eq!("vulkan1.1garbage".parse::<TargetEnv>(), Err(ParseTargetEnvError::Unknown("vulkan1.1garbage".to_owned())));
+    assert_eq!("webgpu0x".parse::<TargetEnv>(), Err(ParseTargetEnvError::Unknown("webgpu0x".to_owned())));
+    assert_eq!(TargetEnv::WebGPU0.to_string(), "webgpu0");
+    assert!(TargetEnv::Vulkan1_1.to_string().contains("Vulkan 1.1"));
+
+    assert_eq!(TargetEnv::Vulkan1_1.max_spirv_version(), (1, 3));
+    assert_eq!(TargetEnv::Vulkan1_2.max_spirv_version(), (1, 5));
+    assert_eq!(TargetEnv::Universal1_6.max_spirv_version(), (1, 6));
+    assert_eq!(TargetEnv::Vulkan1_1.family(), TargetEnvFamily::Vulkan);
+    assert_eq!(TargetEnv::OpenCLEmbedded2_2.family(), TargetEnvFamily::OpenCL);
+    assert_eq!(TargetEnv::OpenGl4_5.family(), TargetEnvFamily::OpenGL);
+    assert_eq!(TargetEnv::Universal1_3.family(), TargetEnvFamily::Universal);
+}
+
+#[test]
+fn library_version() {
+    let version = version();
+
+    assert!(version.year() >= 2020, "Unexpected version '{}'", version);
+    assert!(version.as_str().starts_with('v'));
+    assert!(version.details().contains(version.as_str()), "Unexpected details '{}'", version.details());
+    assert_eq!(version.to_string(), version.as_str());
+
+    if let Some(commit) = version.commit() {
+        assert!(version.details().ends_with(commit));
+    }
+
+    if let Some(commit) = VENDORED_COMMIT {
+        assert_eq!(commit.len(), 40);
+    }
+}
+
+#[test]
+fn optimizer_passes() {
+    let passes = vec![
+        OptimizerPass::Performance,
+        OptimizerPass::EliminateDeadCode,
+        OptimizerPass::InlineEntryPointsExhaustive,
+        OptimizerPass::ScalarReplacement { limit: None },
+        OptimizerPass::ScalarReplacement { limit: Some(100) },
+        OptimizerPass::LoopUnroll,
+        OptimizerPass::IfConversion,
+        OptimizerPass::StripDebugInfo,
+        OptimizerPass::SetSpecConstantDefaultValue(vec![(1, "42".to_owned()), (2, "0.5".to_owned())]),
+        OptimizerPass::LoopUnrollPartial { factor: 2 },
+    ];
+
+    for pass in &passes {
+        assert_eq!(pass.flag().parse::<OptimizerPass>().as_ref().ok(), Some(pass));
+    }
+
+    assert_eq!(OptimizerPass::Size.flag(), "-Os");
+    assert_eq!(OptimizerPass::ScalarReplacement { limit: Some(100) }.flag(), "--scalar-replacement=100");
+    assert_eq!(passes[8].flag(), "--set-spec-const-default-value=1:42 2:0.5");
+
+    assert!("--eliminate-dead-code".parse::<OptimizerPass>().is_err());
+    assert!("--loop-unroll-partial=0".parse::<OptimizerPass>().is_err());
+    assert!("strip-debug".parse::<OptimizerPass>().is_err());
+    assert!("--reduce-load-size=inf".parse::<OptimizerPass>().is_err());
+    assert!("--set-spec-const-default-value".parse::<OptimizerPass>().is_err());
+
+    let unwritable = vec![
+        OptimizerPass::SetSpecConstantDefaultValue(Vec::new()),
+        OptimizerPass::SetSpecConstantDefaultValue(vec![(1, "4 2".to_owned())]),
+        OptimizerPass::SetSpecConstantDefaultValue(vec![(1, "4\0".to_owned())]),
+        OptimizerPass::ConvertToSampledImage(Vec::new()),
+        OptimizerPass::ReduceLoadSize { threshold: Some(f64::INFINITY) },
+        OptimizerPass::ReduceLoadSize { threshold: Some(f64::NAN) },
+    ];
+
+    for pass in unwritable {
+        let registered = Optimizer::new(TargetEnv::OpenGl4_5)
+            .register_pass(pass.clone());
+
+        assert!(matches!(registered, Err(OptimizerError::InvalidFlag(_))), "Registered '{:?}'", pass);
+    }
+
+    assert!(Optimizer::new(TargetEnv::OpenGl4_5).register_pass_from_flag("--strip-debug\0").is_err());
+
+    let ctx = Context::new(TargetEnv::OpenGl4_5);
+    let mut opt = Optimizer::new(TargetEnv::OpenGl4_5);
+    for pass in passes {
+        opt = opt.register_pass(pass)
+            .unwrap();
+    }
+
+    let assembled = ctx.assemble(ASM_SRC)
+        .unwrap();
+    let optimized = opt.run(&assembled)
+        .unwrap();
+
+    ctx.validate(&optimized)
+        .unwrap();
+}
+
+#[test]
+fn optimizer_failure_messages() {
+    let mut opt = Optimizer::new(TargetEnv::OpenGl4_5)
+        .register_performance_passes();
+    opt.set_message_consumer(|_| {});
+    opt.clear_message_consumer();
+
+    let err = opt.run(&[0xDEAD_BEEF, 0, 0, 0, 0])
+        .unwrap_err();
+    assert!(err.to_string().starts_with("Optimization failed: "), "Unexpected error '{}'", err);
+
+    match err {
+        OptimizerError::Failed { messages } => assert!(messages.iter().any(|x| x.level().is_error()), "No errors in '{:?}'", messages),
+        x                                   => panic!("Unexpected error: {:?}", x)
+    }
+
+    let ctx = Context::new(TargetEnv::OpenGl4_5);
+    let assembled = ctx.assemble(ASM_SRC)
+        .unwrap();
+    let (optimized, messages) = opt.run_with_messages(&assembled, &OptimizerOptions::new())
+        .unwrap();
+
+    assert!(messages.iter().all(|x| !x.level().is_error()), "Errors in '{:?}'", messages);
+    ctx.validate(&optimized)
+        .unwrap();
+}
+
+#[test]
+fn shared_optimizer() {
+    let ctx = Context::new(TargetEnv::OpenGl4_5);
+    let assembled = std::sync::Arc::new(ctx.assemble(ASM_SRC).unwrap().into_vec());
+
+    let opt = std::sync::Arc::new(Optimizer::new(TargetEnv::OpenGl4_5)
+        .register_pass(OptimizerPass::EliminateDeadCode)
+        .unwrap()
+        .register_performance_passes());
+    let options = std::sync::Arc::new(OptimizerOptions::new()
+        .run_validator(true)
+        .max_id_bound(0x3FFFFF));
+
+    let workers: Vec<_> = (0..4)
+        .map(|i| {
+            let (assembled, options) = (assembled.clone(), options.clone());
+
+            // Half of the workers share the optimizer, the others run their own clone
+            let opt = opt.clone();
+            let cloned = if i % 2 == 0 { Some((*opt).clone()) } else { None };
+
+            std::thread::spawn(move || {
+                let opt = cloned.as_ref().unwrap_or(&opt);
+                opt.run_with_options(&assembled, &options)
+                    .map(|x| x.into_vec())
+            })
+        })
+        .collect();
+
+    let expected = opt.run_with_options(&assembled, &options.as_ref().clone())
+        .unwrap();
+    ctx.validate(&expected)
+        .unwrap();
+
+    for worker in workers {
+        let optimized = worker.join()
+            .unwrap()
+            .unwrap();
+
+        assert_eq!(&optimized[..], &expected[..]);
+    }
+
+    // Earlier runs don't affect later ones
+    assert_eq!(opt.run(&assembled).unwrap(), opt.run(&assembled).unwrap());
+}
+
+/// Assert that a module fails validation with the default options and passes
+/// once `relax` was applied to them
+fn assert_relaxed_by<F: Fn(ValidatorOptions) -> ValidatorOptions>(env: TargetEnv, source: &str, relax: F) {
+    let ctx = Context::new(env);
+    let assembled = ctx.assemble(source)
+        .unwrap();
+
+    let strict = ctx.validate_with_options(&assembled, &ValidatorOptions::new());
+    assert!(strict.is_err(), "Validation passed without the option");
+
+    let relaxed = ctx.validate_with_options(&assembled, &relax(ValidatorOptions::new()));
+    assert!(relaxed.is_ok(), "Validation failed with the option: '{:?}'", relaxed);
+}
+
+#[test]
+fn validator_before_hlsl_legalization() {
+    // Variables holding pointers are only legal once legalization removed them
+    assert_relaxed_by(TargetEnv::Universal1_3, r#"
+        OpCapability Shader
+        OpMemoryModel Logical GLSL450
+        OpEntryPoint GLCompute %main "main"
+        OpExecutionMode %main LocalSize 1 1 1
+%void = OpTypeVoid
+  %fn = OpTypeFunction %void
+ %int = OpTypeInt 32 1
+%int_ptr = OpTypePointer Function %int
+%int_ptr_ptr = OpTypePointer Function %int_ptr
+%main = OpFunction %void None %fn
+%entry = OpLabel
+ %var = OpVariable %int_ptr_ptr Function
+        OpReturn
+        OpFunctionEnd
+    "#, |x| x.before_hlsl_legalization(true));
+}
+
+#[test]
+fn validator_uniform_buffer_standard_layout() {
+    // A tightly packed array is std430 layout, which uniform buffers don't use by
+    // default
+    assert_relaxed_by(TargetEnv::Vulkan1_1, r#"
+        OpCapability Shader
+        OpMemoryModel Logical GLSL450
+        OpEntryPoint GLCompute %main "main"
+        OpExecutionMode %main LocalSize 1 1 1
+        OpDecorate %array ArrayStride 4
+        OpMemberDecorate %block 0 Offset 0
+        OpDecorate %block Block
+        OpDecorate %ubo DescriptorSet 0
+        OpDecorate %ubo Binding 0
+ %void = OpTypeVoid
+   %fn = OpTypeFunction %void
+%float = OpTypeFloat 32
+ %uint = OpTypeInt 32 0
+%uint_4 = OpConstant %uint 4
+%array = OpTypeArray %float %uint_4
+%block = OpTypeStruct %array
+  %ptr = OpTypePointer Uniform %block
+  %ubo = OpVariable %ptr Uniform
+ %main = OpFunction %void None %fn
+%entry = OpLabel
+        OpReturn
+        OpFunctionEnd
+    "#, |x| x.uniform_buffer_standard_layout(true));
+}
+
+#[test]
+fn validator_workgroup_scalar_block_layout() {
+    // A vector straight after a float is only aligned under scalar layout
+    assert_relaxed_by(TargetEnv::Vulkan1_2, r#"
+        OpCapability Shader
+        OpCapability WorkgroupMemoryExplicitLayoutKHR
+        OpExtension "SPV_KHR_workgroup_memory_explicit_layout"
+        OpMemoryModel Logical GLSL450
+        OpEntryPoint GLCompute %main "main" %shared
+        OpExecutionMode %main LocalSize 1 1 1
+        OpMemberDecorate %block 0 Offset 0
+        OpMemberDecorate %block 1 Offset 4
+        OpDecorate %block Block
+ %void = OpTypeVoid
+   %fn = OpTypeFunction %void
+%float = OpTypeFloat 32
+ %vec4 = OpTypeVector %float 4
+%block = OpTypeStruct %float %vec4
+  %ptr = OpTypePointer Workgroup %block
+%shared = OpVariable %ptr Workgroup
+ %main = OpFunction %void None %fn
+%entry = OpLabel
+        OpReturn
+        OpFunctionEnd
+    "#, |x| x.workgroup_scalar_block_layout(true));
+}
+
+#[test]
+fn validator_allow_local_size_id() {
+    // LocalSizeId needs Vulkan 1.3 or the maintenance4 extension
+    assert_relaxed_by(TargetEnv::Vulkan1_1, r#"
+        OpCapability Shader
+        OpMemoryModel Logical GLSL450
+        OpEntryPoint GLCompute %main "main"
+        OpExecutionModeId %main LocalSizeId %uint_1 %uint_1 %uint_1
+ %void = OpTypeVoid
+   %fn = OpTypeFunction %void
+ %uint = OpTypeInt 32 0
+%uint_1 = OpConstant %uint 1
+ %main = OpFunction %void None %fn
+%entry = OpLabel
+        OpReturn
+        OpFunctionEnd
+    "#, |x| x.allow_local_size_id(true));
+}
+
+#[test]
+fn validator_friendly_names() {
+    let ctx = Context::new(TargetEnv::Universal1_3)
+        .with_diagnostics();
+    let assembled = ctx.assemble(r#"
+        OpCapability Shader
+        OpMemoryModel Logical GLSL450
+        OpEntryPoint GLCompute %main "main"
+        OpExecutionMode %main LocalSize 1 1 1
+        OpName %main "main"
+%void = OpTypeVoid
+  %fn = OpTypeFunction %void
+%main = OpFunction %void None %fn
+%entry = OpLabel
+        OpReturnValue %main
+        OpFunctionEnd
+    "#).unwrap();
+
+    let message = |friendly_names| match ctx.validate_with_options(&assembled, &ValidatorOptions::new().friendly_names(friendly_names)) {
+        Err(ValidateError::SpirvTools(_, Some(diag))) => diag.message().to_owned(),
+        x                                             => panic!("Unexpected result: {:?}", x)
+    };
+
+    let friendly = message(true);
+    let numbered = message(false);
+
+    assert!(friendly.contains("[%main]"), "Unexpected message '{}'", friendly);
+    assert!(numbered.contains("[%1]"), "Unexpected message '{}'", numbered);
+}
+
+#[test]
+fn reusable_validator_options() {
+    let ctx = Context::new(TargetEnv::OpenGl4_5);
+    let assembled = ctx.assemble(ASM_SRC)
+        .unwrap();
+
+    // The module needs more than 10 ids
+    let strict = ValidatorOptions::new()
+        .limit(ValidatorLimit::MaxStructMembers(64))
+        .limit(ValidatorLimit::MaxIdBound(10))
+        .friendly_names(false);
+
+    assert_eq!(strict.settings().limits, vec![ValidatorLimit::MaxStructMembers(64), ValidatorLimit::MaxIdBound(10)]);
+    assert_eq!(strict.settings().friendly_names, Some(false));
+    assert_eq!(strict.settings().scalar_block_layout, None);
+    assert!(format!("{:?}", strict).contains("MaxIdBound(10)"));
+
+    // Later limits replace earlier ones of the same kind
+    let relaxed = strict.clone()
+        .limit(ValidatorLimit::MaxIdBound(100));
+
+    assert_eq!(relaxed.settings().limits, vec![ValidatorLimit::MaxStructMembers(64), ValidatorLimit::MaxIdBound(100)]);
+    assert_ne!(strict, relaxed);
+    assert_eq!(strict, strict.clone());
+
+    // Clones are configured the same as the original, and both can be reused
+    for options in &[&strict, &strict.clone()] {
+        assert!(ctx.validate_with_options(&assembled, options).is_err());
+        assert!(ctx.validate_with_options(&assembled, options).is_err());
+    }
+
+    ctx.validate_with_options(&assembled, &relaxed)
+        .unwrap();
+
+    // Legalization also relaxes logical pointers
+    let legalization = ValidatorOptions::new()
+        .before_hlsl_legalization(true);
+
+    assert_eq!(legalization.settings().relax_logical_pointer, Some(true));
+    assert_eq!(ValidatorOptions::from(legalization.settings().clone()), legalization);
+
+    // The optimizer uses the options without taking them
+    let opt = Optimizer::new(TargetEnv::OpenGl4_5)
+        .register_performance_passes();
+    let run = |options| opt.run_with_options(&assembled, &OptimizerOptions::new()
+        .run_validator(true)
+        .validator_options(options));
+
+    assert!(run(&strict).is_err());
+    assert!(run(&relaxed).is_ok());
+}
+
+#[cfg(feature = "serde")]
+#[test]
+fn serialize_validator_options() {
+    let options = ValidatorOptions::new()
+        .limit(ValidatorLimit::MaxIdBound(100))
+        .scalar_block_layout(true);
+
+    let json = serde_json::to_string(&options)
+        .unwrap();
+    let deserialized: ValidatorOptions = serde_json::from_str(&json)
+        .unwrap();
+
+    assert_eq!(deserialized, options);
+
+    // Missing values use the spirv-tools defaults
+    let partial: ValidatorOptions = serde_json::from_str(r#"{ "skip_block_layout": true }"#)
+        .unwrap();
+
+    assert_eq!(partial, ValidatorOptions::new().skip_block_layout(true));
+}
+
+#[test]
+fn device_profile_options() {
+    let baseline = DeviceProfile::default();
+    assert_eq!(baseline.target_env(), TargetEnv::Vulkan1_0);
+    assert_eq!(ValidatorOptions::from_device_profile(&baseline).settings().scalar_block_layout, Some(false));
+
+    // The limits default to the minimums Vulkan requires and spirv-tools has no
+    // options for them
+    assert_eq!(baseline.max_push_constants_size, 128);
+    assert_eq!(baseline.max_compute_work_group_size, [128, 128, 64]);
+    assert_eq!(baseline.max_compute_work_group_invocations, 128);
+
+    let limited = DeviceProfile {
+        max_push_constants_size: 0,
+        max_compute_work_group_size: [1, 1, 1],
+        max_compute_work_group_invocations: 1,
+        ..DeviceProfile::default()
+    };
+    assert_eq!(ValidatorOptions::from_device_profile(&limited).settings(), ValidatorOptions::from_device_profile(&baseline).settings());
+
+    let device = DeviceProfile {
+        api_version: (1, 2),
+        scalar_block_layout: true,
+        ..DeviceProfile::default()
+    };
+    assert_eq!(device.target_env(), TargetEnv::Vulkan1_2);
+
+    let options = ValidatorOptions::from_device_profile(&device);
+    assert_eq!(options.settings().relax_block_layout, Some(true));
+    assert_eq!(options.settings().scalar_block_layout, Some(true));
+    assert_eq!(options.settings().uniform_buffer_standard_layout, Some(false));
+    assert_eq!(options.settings().allow_local_size_id, Some(false));
+
+    // A vector straddling a 16 byte boundary needs scalar block layout
+    let ctx = Context::new(device.target_env());
+    let assembled = ctx.assemble(r#"
+        OpCapability Shader
+        OpMemoryModel Logical GLSL450
+        OpEntryPoint GLCompute %main "main"
+        OpExecutionMode %main LocalSize 1 1 1
+        OpMemberDecorate %block 0 Offset 0
+        OpMemberDecorate %block 1 Offset 4
+        OpDecorate %block Block
+        OpDecorate %ubo DescriptorSet 0
+        OpDecorate %ubo Binding 0
+ %void = OpTypeVoid
+   %fn = OpTypeFunction %void
+%float = OpTypeFloat 32
+ %vec4 = OpTypeVector %float 4
+%block = OpTypeStruct %float %vec4
+  %ptr = OpTypePointer Uniform %block
+  %ubo = OpVariable %ptr Uniform
+ %main = OpFunction %void None %fn
+%entry = OpLabel
+        OpReturn
+        OpFunctionEnd
+    "#).unwrap();
+
+    ctx.validate_with_options(&assembled, &options)
+        .unwrap();
+
+    let without_scalar = DeviceProfile { scalar_block_layout: false, ..device };
+    assert!(ctx.validate_with_options(&assembled, &ValidatorOptions::from_device_profile(&without_scalar)).is_err());
+}
+
+#[test]
+fn validation_matrix() {
+    let source = r#"
+        OpCapability Shader
+        OpMemoryModel Logical GLSL450
+        OpEntryPoint GLCompute %main "main"
+        OpExecutionMode %main LocalSize 1 1 1
+%void = OpTypeVoid
+  %fn = OpTypeFunction %void
+%main = OpFunction %void None %fn
+%entry = OpLabel
+        OpReturn
+        OpFunctionEnd
+    "#;
+
+    // A SPIR-V 1.3 module is too new for Vulkan 1.0 and OpenGL
+    let assembled = Context::new(TargetEnv::Universal1_3)
+        .assemble(source)
+        .unwrap();
+
+    let targets = [TargetEnv::Vulkan1_0, TargetEnv::Vulkan1_1, TargetEnv::Vulkan1_2, TargetEnv::OpenGl4_5];
+    let report = validate_for_targets(&assembled, &targets, &ValidatorOptions::new());
+
+    let envs: Vec<_> = report.targets().iter().map(|x| x.env).collect();
+    assert_eq!(envs, targets);
+
+    assert!(!report.is_valid());
+    assert_eq!(report.valid_targets(), vec![TargetEnv::Vulkan1_1, TargetEnv::Vulkan1_2]);
+    assert_eq!(report.failed_targets(), vec![TargetEnv::Vulkan1_0, TargetEnv::OpenGl4_5]);
+    assert_eq!(report.minimal_targets(), vec![TargetEnv::Vulkan1_1]);
+
+    // Failures carry the diagnostic of the first error
+    match report.result(TargetEnv::Vulkan1_0) {
+        Some(Err(ValidateError::SpirvTools(_, Some(_)))) => (),
+        x                                                => panic!("Unexpected result: {:?}", x)
+    }
+    assert!(report.result(TargetEnv::Universal1_0).is_none());
+
+    // A module valid everywhere only needs the oldest version of each API
+    let assembled = Context::new(TargetEnv::Universal1_0)
+        .assemble(source)
+        .unwrap();
+    let report = validate_for_targets(&assembled, &[TargetEnv::OpenGl4_5, TargetEnv::Vulkan1_1, TargetEnv::Vulkan1_0, TargetEnv::OpenGL4_0], &ValidatorOptions::new());
+
+    assert!(report.is_valid(), "{:?}", report);
+    assert_eq!(report.minimal_targets(), vec![TargetEnv::Vulkan1_0, TargetEnv::OpenGL4_0]);
+}
+
+#[test]
+fn validate_all_diagnostics() {
+    let ctx = Context::new(TargetEnv::Universal1_3);
+    let assembled = ctx.assemble(r#"
+        OpCapability Shader
+        OpMemoryModel Logical GLSL450
+        OpEntryPoint GLCompute %main "main"
+        OpExecutionMode %main LocalSize 1 1 1
+%void = OpTypeVoid
+  %fn = OpTypeFunction %void
+%main = OpFunction %void None %fn
+%entry = OpLabel
+        OpReturnValue %main
+        OpFunctionEnd
+    "#).unwrap();
+
+    let (err, diagnostics) = match ctx.validate_all(&assembled, &ValidatorOptions::new()) {
+        Err(ValidateError::Failed { err, diagnostics }) => (err, diagnostics),
+        x                                               => panic!("Unexpected result: {:?}", x)
+    };
+
+    // The error points at the OpReturnValue
+    let error = diagnostics.iter()
+        .find(|x| x.severity() == MessageLevel::Error)
+        .unwrap();
+    let offset = error.word_offset()
+        .unwrap();
+
+    assert_eq!(assembled[offset] & 0xFFFF, 254, "{}", error);
+    assert!(error.to_string().starts_with(&format!("error at word {}: ", offset)));
+
+    let failed = ctx.validate_all(&assembled, &ValidatorOptions::new())
+        .unwrap_err();
+    let message = failed.to_string();
+    assert_eq!(message, format!("Validation failed ({}) with 1 error: {}", err, error.message()));
+
+    let source = std::error::Error::source(&failed)
+        .and_then(|x| x.downcast_ref::<SpvError>());
+    assert_eq!(source, Some(&err));
+
+    // The error is counted under the category validation failed with
+    assert_eq!(error.category(), Some(&err));
+    assert_eq!(failed.counts(), std::iter::once((err.clone(), 1)).collect());
+
+    // An installed consumer still receives the messages, even with diagnostics
+    // included
+    let received = std::sync::Arc::new(std::sync::Mutex::new(Vec::new()));
+    let sink = received.clone();
+    let mut ctx = ctx.with_diagnostics();
+    ctx.set_message_consumer(move |x| sink.lock().unwrap().push(x));
+
+    let collected = match ctx.validate_all(&assembled, &ValidatorOptions::new()) {
+        Err(ValidateError::Failed { diagnostics, .. }) => diagnostics,
+        x                                              => panic!("Unexpected result: {:?}", x)
+    };
+    assert_eq!(collected, diagnostics);
+    assert_eq!(received.lock().unwrap().len(), diagnostics.len());
+
+    // Other validations return the first error in the diagnostic instead
+    received.lock().unwrap().clear();
+    match ctx.validate(&assembled) {
+        Err(ValidateError::SpirvTools(_, Some(_))) => (),
+        x                                          => panic!("Expected a diagnostic, got '{:?}'", x)
+    }
+    assert!(received.lock().unwrap().is_empty());
+
+    // Errors about the module as a whole have no offset
+    let mut truncated = Context::new(TargetEnv::Universal1_3)
+        .assemble(ASM_SRC)
+        .unwrap()
+        .into_vec();
+    truncated.pop();
+
+    match Context::new(TargetEnv::Universal1_3).validate_all(&truncated, &ValidatorOptions::new()) {
+        Err(ValidateError::Failed { diagnostics, .. }) => assert_eq!(diagnostics[0].word_offset(), None),
+        x                                              => panic!("Unexpected result: {:?}", x)
+    }
+
+    // Binaries that don't parse report the instruction the parser stopped at. The
+    // last OpFunctionEnd claims to be two words long.
+    let last = truncated.len();
+    truncated.push((2 << 16) | 56);
+
+    match Context::new(TargetEnv::Universal1_3).validate_all(&truncated, &ValidatorOptions::new()) {
+        Err(ValidateError::Failed { diagnostics, .. }) => assert_eq!(diagnostics[0].word_offset(), Some(last), "{}", diagnostics[0]),
+        x                                              => panic!("Unexpected result: {:?}", x)
+    }
+
+    // Valid binaries return the warnings, of which there are none here
+    let valid = Context::new(TargetEnv::Universal1_3)
+        .assemble(ASM_SRC)
+        .unwrap();
+    assert_eq!(ctx.validate_all(&valid, &ValidatorOptions::new()).unwrap(), vec![]);
+}
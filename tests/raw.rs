@@ -0,0 +1,152 @@
+extern crate spirv_tools_rs;
+
+use std::ffi::CStr;
+use std::mem::{align_of, offset_of, size_of};
+use std::os::raw::{c_char, c_longlong};
+use std::slice;
+
+use spirv_tools_rs::raw::*;
+
+// Values and layouts below are compared against `src/c/probe.c`, which the C
+// compiler evaluates from the same `libspirv.h` the crate is built against, so
+// these fail if the bindings drift from the headers.
+
+#[repr(C)]
+struct Probe {
+    name: *const c_char,
+    value: c_longlong
+}
+
+// Built by `build.rs`, but only linked here so the library doesn't carry it
+#[link(name = "spirv-tools-rs-probe", kind = "static")]
+extern "C" {
+    static spvrs_probes: [Probe; 0];
+    static spvrs_probe_count: usize;
+}
+
+/// Look up a value the C compiler evaluated by its expression
+fn probe(name: &str) -> i64 {
+    let probes = unsafe { slice::from_raw_parts(spvrs_probes.as_ptr(), spvrs_probe_count) };
+
+    probes.iter()
+        .find(|x| unsafe { CStr::from_ptr(x.name) }.to_str() == Ok(name))
+        .map(|x| x.value)
+        .unwrap_or_else(|| panic!("`{}` is not probed in src/c/probe.c", name))
+}
+
+macro_rules! assert_probe {
+    ($value:expr, $name:expr) => {
+        assert_eq!($value as i64, probe($name), "{} != {}", stringify!($value), $name)
+    };
+}
+
+#[test]
+fn result_values() {
+    assert_probe!(spv_result_t::SUCCESS.0,                "SPV_SUCCESS");
+    assert_probe!(spv_result_t::REQUESTED_TERMINATION.0,  "SPV_REQUESTED_TERMINATION");
+    assert_probe!(spv_result_t::ERROR_INTERNAL.0,         "SPV_ERROR_INTERNAL");
+    assert_probe!(spv_result_t::ERROR_INVALID_BINARY.0,   "SPV_ERROR_INVALID_BINARY");
+    assert_probe!(spv_result_t::ERROR_INVALID_DATA.0,     "SPV_ERROR_INVALID_DATA");
+    assert_probe!(spv_result_t::ERROR_WRONG_VERSION.0,    "SPV_ERROR_WRONG_VERSION");
+}
+
+#[test]
+fn enum_values() {
+    assert_probe!(spv_message_level_t::FATAL.0,                             "SPV_MSG_FATAL");
+    assert_probe!(spv_message_level_t::DEBUG.0,                             "SPV_MSG_DEBUG");
+    assert_probe!(spv_endianness_t::BIG.0,                                  "SPV_ENDIANNESS_BIG");
+    assert_probe!(spv_operand_type_t::LITERAL_INTEGER.0,                    "SPV_OPERAND_TYPE_LITERAL_INTEGER");
+    assert_probe!(spv_operand_type_t::MEMORY_ACCESS.0,                      "SPV_OPERAND_TYPE_MEMORY_ACCESS");
+    assert_probe!(spv_operand_type_t::FRAGMENT_SHADING_RATE.0,              "SPV_OPERAND_TYPE_FRAGMENT_SHADING_RATE");
+    assert_probe!(spv_operand_type_t::OPTIONAL_CIV.0,                       "SPV_OPERAND_TYPE_OPTIONAL_CIV");
+    assert_probe!(spv_operand_type_t::VARIABLE_ID.0,                        "SPV_OPERAND_TYPE_VARIABLE_ID");
+    assert_probe!(spv_operand_type_t::CLDEBUG100_DEBUG_IMPORTED_ENTITY.0,   "SPV_OPERAND_TYPE_CLDEBUG100_DEBUG_IMPORTED_ENTITY");
+    assert_probe!(spv_operand_type_t::OPTIONAL_PACKED_VECTOR_FORMAT.0,      "SPV_OPERAND_TYPE_OPTIONAL_PACKED_VECTOR_FORMAT");
+    assert_probe!(spv_operand_type_t::NUM_OPERAND_TYPES.0,                  "SPV_OPERAND_TYPE_NUM_OPERAND_TYPES");
+    assert_probe!(spv_ext_inst_type_t::DEBUGINFO.0,                         "SPV_EXT_INST_TYPE_DEBUGINFO");
+    assert_probe!(spv_ext_inst_type_t::NONSEMANTIC_CLSPVREFLECTION.0,       "SPV_EXT_INST_TYPE_NONSEMANTIC_CLSPVREFLECTION");
+    assert_probe!(spv_ext_inst_type_t::NONSEMANTIC_SHADER_DEBUGINFO_100.0,  "SPV_EXT_INST_TYPE_NONSEMANTIC_SHADER_DEBUGINFO_100");
+    assert_probe!(spv_ext_inst_type_t::NONSEMANTIC_UNKNOWN.0,               "SPV_EXT_INST_TYPE_NONSEMANTIC_UNKNOWN");
+    assert_probe!(spv_number_kind_t::FLOATING.0,                            "SPV_NUMBER_FLOATING");
+}
+
+#[test]
+fn option_values() {
+    assert_probe!(spv_text_to_binary_options_t::PRESERVE_NUMERIC_IDS.0,  "SPV_TEXT_TO_BINARY_OPTION_PRESERVE_NUMERIC_IDS");
+    assert_probe!(spv_binary_to_text_options_t::PRINT.0,                 "SPV_BINARY_TO_TEXT_OPTION_PRINT");
+    assert_probe!(spv_binary_to_text_options_t::FRIENDLY_NAMES.0,        "SPV_BINARY_TO_TEXT_OPTION_FRIENDLY_NAMES");
+    assert_probe!(kDefaultMaxIdBound,                                    "kDefaultMaxIdBound");
+}
+
+#[test]
+fn target_env_values() {
+    assert_probe!(spv_target_env::UNIVERSAL_1_0.0,         "SPV_ENV_UNIVERSAL_1_0");
+    assert_probe!(spv_target_env::VULKAN_1_0.0,            "SPV_ENV_VULKAN_1_0");
+    assert_probe!(spv_target_env::UNIVERSAL_1_1.0,         "SPV_ENV_UNIVERSAL_1_1");
+    assert_probe!(spv_target_env::OPENCL_2_1.0,            "SPV_ENV_OPENCL_2_1");
+    assert_probe!(spv_target_env::OPENCL_2_2.0,            "SPV_ENV_OPENCL_2_2");
+    assert_probe!(spv_target_env::OPENGL_4_0.0,            "SPV_ENV_OPENGL_4_0");
+    assert_probe!(spv_target_env::OPENGL_4_1.0,            "SPV_ENV_OPENGL_4_1");
+    assert_probe!(spv_target_env::OPENGL_4_2.0,            "SPV_ENV_OPENGL_4_2");
+    assert_probe!(spv_target_env::OPENGL_4_3.0,            "SPV_ENV_OPENGL_4_3");
+    assert_probe!(spv_target_env::OPENGL_4_5.0,            "SPV_ENV_OPENGL_4_5");
+    assert_probe!(spv_target_env::UNIVERSAL_1_2.0,         "SPV_ENV_UNIVERSAL_1_2");
+    assert_probe!(spv_target_env::OPENCL_1_2.0,            "SPV_ENV_OPENCL_1_2");
+    assert_probe!(spv_target_env::OPENCL_EMBEDDED_1_2.0,   "SPV_ENV_OPENCL_EMBEDDED_1_2");
+    assert_probe!(spv_target_env::OPENCL_2_0.0,            "SPV_ENV_OPENCL_2_0");
+    assert_probe!(spv_target_env::OPENCL_EMBEDDED_2_0.0,   "SPV_ENV_OPENCL_EMBEDDED_2_0");
+    assert_probe!(spv_target_env::OPENCL_EMBEDDED_2_1.0,   "SPV_ENV_OPENCL_EMBEDDED_2_1");
+    assert_probe!(spv_target_env::OPENCL_EMBEDDED_2_2.0,   "SPV_ENV_OPENCL_EMBEDDED_2_2");
+    assert_probe!(spv_target_env::UNIVERSAL_1_3.0,         "SPV_ENV_UNIVERSAL_1_3");
+    assert_probe!(spv_target_env::VULKAN_1_1.0,            "SPV_ENV_VULKAN_1_1");
+    assert_probe!(spv_target_env::WEBGPU_0.0,              "SPV_ENV_WEBGPU_0");
+    assert_probe!(spv_target_env::UNIVERSAL_1_4.0,         "SPV_ENV_UNIVERSAL_1_4");
+    assert_probe!(spv_target_env::VULKAN_1_1_SPIRV_1_4.0,  "SPV_ENV_VULKAN_1_1_SPIRV_1_4");
+    assert_probe!(spv_target_env::UNIVERSAL_1_5.0,         "SPV_ENV_UNIVERSAL_1_5");
+    assert_probe!(spv_target_env::VULKAN_1_2.0,            "SPV_ENV_VULKAN_1_2");
+    assert_probe!(spv_target_env::UNIVERSAL_1_6.0,         "SPV_ENV_UNIVERSAL_1_6");
+    assert_probe!(spv_target_env::VULKAN_1_3.0,            "SPV_ENV_VULKAN_1_3");
+}
+
+#[test]
+fn validator_limit_values() {
+    assert_probe!(spv_validator_limit::max_struct_members.0,              "spv_validator_limit_max_struct_members");
+    assert_probe!(spv_validator_limit::max_struct_depth.0,                "spv_validator_limit_max_struct_depth");
+    assert_probe!(spv_validator_limit::max_local_variables.0,             "spv_validator_limit_max_local_variables");
+    assert_probe!(spv_validator_limit::max_global_variables.0,            "spv_validator_limit_max_global_variables");
+    assert_probe!(spv_validator_limit::max_switch_branches.0,             "spv_validator_limit_max_switch_branches");
+    assert_probe!(spv_validator_limit::max_function_args.0,               "spv_validator_limit_max_function_args");
+    assert_probe!(spv_validator_limit::max_control_flow_nesting_depth.0,  "spv_validator_limit_max_control_flow_nesting_depth");
+    assert_probe!(spv_validator_limit::max_access_chain_indexes.0,        "spv_validator_limit_max_access_chain_indexes");
+    assert_probe!(spv_validator_limit::max_id_bound.0,                    "spv_validator_limit_max_id_bound");
+}
+
+#[test]
+fn struct_layouts() {
+    assert_probe!(size_of::<spv_position_t>(),         "sizeof(spv_position_t)");
+    assert_probe!(offset_of!(spv_position_t, column),  "offsetof(spv_position_t, column)");
+    assert_probe!(offset_of!(spv_position_t, index),   "offsetof(spv_position_t, index)");
+
+    assert_probe!(size_of::<spv_diagnostic_t>(),               "sizeof(spv_diagnostic_t)");
+    assert_probe!(offset_of!(spv_diagnostic_t, error),         "offsetof(spv_diagnostic_t, error)");
+    assert_probe!(offset_of!(spv_diagnostic_t, isTextSource),  "offsetof(spv_diagnostic_t, isTextSource)");
+
+    assert_probe!(size_of::<spv_parsed_operand_t>(),                   "sizeof(spv_parsed_operand_t)");
+    assert_probe!(align_of::<spv_parsed_operand_t>(),                  "alignof(spv_parsed_operand_t)");
+    assert_probe!(offset_of!(spv_parsed_operand_t, num_words),         "offsetof(spv_parsed_operand_t, num_words)");
+    assert_probe!(offset_of!(spv_parsed_operand_t, type_),             "offsetof(spv_parsed_operand_t, type)");
+    assert_probe!(offset_of!(spv_parsed_operand_t, number_kind),       "offsetof(spv_parsed_operand_t, number_kind)");
+    assert_probe!(offset_of!(spv_parsed_operand_t, number_bit_width),  "offsetof(spv_parsed_operand_t, number_bit_width)");
+
+    assert_probe!(size_of::<spv_parsed_instruction_t>(),               "sizeof(spv_parsed_instruction_t)");
+    assert_probe!(offset_of!(spv_parsed_instruction_t, num_words),     "offsetof(spv_parsed_instruction_t, num_words)");
+    assert_probe!(offset_of!(spv_parsed_instruction_t, opcode),        "offsetof(spv_parsed_instruction_t, opcode)");
+    assert_probe!(offset_of!(spv_parsed_instruction_t, ext_inst_type), "offsetof(spv_parsed_instruction_t, ext_inst_type)");
+    assert_probe!(offset_of!(spv_parsed_instruction_t, result_id),     "offsetof(spv_parsed_instruction_t, result_id)");
+    assert_probe!(offset_of!(spv_parsed_instruction_t, operands),      "offsetof(spv_parsed_instruction_t, operands)");
+    assert_probe!(offset_of!(spv_parsed_instruction_t, num_operands),  "offsetof(spv_parsed_instruction_t, num_operands)");
+
+    assert_probe!(size_of::<spv_const_binary_t>(),  "sizeof(spv_const_binary_t)");
+    assert_probe!(size_of::<spv_binary_t>(),        "sizeof(spv_binary_t)");
+    assert_probe!(size_of::<spv_text_t>(),          "sizeof(spv_text_t)");
+}